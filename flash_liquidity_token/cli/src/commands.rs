@@ -0,0 +1,482 @@
+//
+// Commands
+//
+// Arguments and handlers of the `flt` subcommands. Amounts are in base units of the token.
+//
+
+use std::collections::BTreeMap;
+
+use anchor_spl::token::{Mint, TokenAccount};
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::Args;
+use flash_liquidity_token::{
+    BorrowerState, CollateralConfig, CollateralParams, CollateralStatus, FeeCurve, Governance, GovernanceParams, Loan, LoanMode,
+    ParameterChange, RewardPool, SecondaryOracle, Staker,
+};
+use flash_liquidity_token_sdk::{instructions, pda, quote};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::context::Context;
+use crate::output::{GovernanceView, PoolStats};
+
+/// Governance parameters. Unset fields keep their current value (`governance set`) or the
+/// `init` default.
+#[derive(Args, Default)]
+pub struct GovernanceArgs {
+    /// Slots after the due slot before a loan can be liquidated [init default: 150].
+    #[arg(long)]
+    pub liquidation_grace_slots: Option<u64>,
+    /// Numerator of the per-slot compound rate [init default: 1].
+    #[arg(long)]
+    pub compound_rate_numerator: Option<u64>,
+    /// Denominator of the per-slot compound rate [init default: 100].
+    #[arg(long)]
+    pub compound_rate_denominator: Option<u64>,
+    /// Share of each fee reserved for claim_rewards [init default: 5000].
+    #[arg(long)]
+    pub reward_share_bps: Option<u64>,
+    /// Share of each fee set aside in the insurance fund [init default: 1000].
+    #[arg(long)]
+    pub insurance_share_bps: Option<u64>,
+    /// Delay between queueing and executing a parameter change [init default: 0].
+    #[arg(long)]
+    pub timelock_delay_slots: Option<u64>,
+    /// Collateral whose FLT carries voting power [init default: none].
+    #[arg(long)]
+    pub vote_collateral_mint: Option<Pubkey>,
+    /// Length of a proposal's voting window [init default: 216000].
+    #[arg(long)]
+    pub voting_period_slots: Option<u64>,
+    /// Votes needed, as a share of the FLT supply [init default: 1000].
+    #[arg(long)]
+    pub quorum_bps: Option<u64>,
+    /// Yes votes needed, as a share of votes cast [init default: 5000].
+    #[arg(long)]
+    pub approval_bps: Option<u64>,
+}
+
+impl GovernanceArgs {
+    /// Parameters used by `init` for fields not given on the command line.
+    pub fn init_defaults() -> GovernanceParams {
+        GovernanceParams {
+            liquidation_grace_slots: 150,
+            compound_rate_numerator: 1,
+            compound_rate_denominator: 100,
+            reward_share_bps: 5_000,
+            insurance_share_bps: 1_000,
+            timelock_delay_slots: 0,
+            vote_collateral_mint: Pubkey::default(),
+            voting_period_slots: 216_000,
+            quorum_bps: 1_000,
+            approval_bps: 5_000,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.liquidation_grace_slots.is_none()
+            && self.compound_rate_numerator.is_none()
+            && self.compound_rate_denominator.is_none()
+            && self.reward_share_bps.is_none()
+            && self.insurance_share_bps.is_none()
+            && self.timelock_delay_slots.is_none()
+            && self.vote_collateral_mint.is_none()
+            && self.voting_period_slots.is_none()
+            && self.quorum_bps.is_none()
+            && self.approval_bps.is_none()
+    }
+
+    /// `base` with every field given on the command line replaced.
+    pub fn apply(&self, base: GovernanceParams) -> GovernanceParams {
+        GovernanceParams {
+            liquidation_grace_slots: self.liquidation_grace_slots.unwrap_or(base.liquidation_grace_slots),
+            compound_rate_numerator: self.compound_rate_numerator.unwrap_or(base.compound_rate_numerator),
+            compound_rate_denominator: self.compound_rate_denominator.unwrap_or(base.compound_rate_denominator),
+            reward_share_bps: self.reward_share_bps.unwrap_or(base.reward_share_bps),
+            insurance_share_bps: self.insurance_share_bps.unwrap_or(base.insurance_share_bps),
+            timelock_delay_slots: self.timelock_delay_slots.unwrap_or(base.timelock_delay_slots),
+            vote_collateral_mint: self.vote_collateral_mint.unwrap_or(base.vote_collateral_mint),
+            voting_period_slots: self.voting_period_slots.unwrap_or(base.voting_period_slots),
+            quorum_bps: self.quorum_bps.unwrap_or(base.quorum_bps),
+            approval_bps: self.approval_bps.unwrap_or(base.approval_bps),
+        }
+    }
+}
+
+/// Parse `none`, `manual` or `pyth:<FEED>`.
+pub fn parse_secondary_oracle(value: &str) -> Result<SecondaryOracle> {
+    match value {
+        "none" => Ok(SecondaryOracle::None),
+        "manual" => Ok(SecondaryOracle::Manual),
+        _ => match value.strip_prefix("pyth:") {
+            Some(feed) => Ok(SecondaryOracle::Pyth(feed.parse().map_err(|_| anyhow!("invalid Pyth feed {feed}"))?)),
+            None => bail!("expected none, manual or pyth:<FEED>"),
+        },
+    }
+}
+
+#[derive(Args)]
+pub struct ListCollateralArgs {
+    /// Collateral mint to list.
+    #[arg(long)]
+    pub mint: Pubkey,
+    /// Pyth price account of the collateral.
+    #[arg(long)]
+    pub pyth_feed: Pubkey,
+    /// Largest term loan as a share of the borrower's stake.
+    #[arg(long)]
+    pub max_borrow_ratio_bps: u64,
+    /// Late penalty per overdue slot, also the liquidation bonus.
+    #[arg(long)]
+    pub liquidation_penalty_bps: u64,
+    /// Largest principal outstanding against the collateral.
+    #[arg(long)]
+    pub borrow_cap: u64,
+    /// Oldest accepted oracle price.
+    #[arg(long, default_value_t = 60)]
+    pub max_price_age_secs: u64,
+    /// Fallback price source: none, manual or pyth:<FEED>.
+    #[arg(long, default_value = "none", value_parser = parse_secondary_oracle)]
+    pub secondary_oracle: SecondaryOracle,
+    /// Largest accepted gap between the primary and secondary price.
+    #[arg(long, default_value_t = 0)]
+    pub max_deviation_bps: u64,
+    /// Fee at 0% utilization.
+    #[arg(long)]
+    pub base_fee_bps: u64,
+    /// Fee added between 0% and the optimal utilization.
+    #[arg(long)]
+    pub slope1_bps: u64,
+    /// Fee added between the optimal utilization and 100%.
+    #[arg(long)]
+    pub slope2_bps: u64,
+    /// The kink of the fee curve.
+    #[arg(long)]
+    pub optimal_utilization_bps: u64,
+    /// Collateral price, in quote units with 9 decimals, at which the oracle leaves the curve fee
+    /// unchanged; 0 disables the oracle adjustment.
+    #[arg(long, default_value_t = 0)]
+    pub reference_price: u64,
+}
+
+#[derive(Args)]
+pub struct StakeArgs {
+    #[arg(long)]
+    pub mint: Pubkey,
+    /// Collateral to deposit.
+    #[arg(long)]
+    pub amount: u64,
+    /// Slots the stake stays locked.
+    #[arg(long, default_value_t = 0)]
+    pub lock_slots: u64,
+}
+
+#[derive(Args)]
+pub struct UnstakeArgs {
+    #[arg(long)]
+    pub mint: Pubkey,
+    /// FLT to unstake, withdraw or redeem.
+    #[arg(long)]
+    pub amount: u64,
+}
+
+#[derive(Args)]
+pub struct BorrowArgs {
+    #[arg(long)]
+    pub mint: Pubkey,
+    #[arg(long)]
+    pub amount: u64,
+    /// Term of the loan.
+    #[arg(long)]
+    pub duration_slots: u64,
+}
+
+#[derive(Args)]
+pub struct RepayArgs {
+    /// Loan account to repay.
+    #[arg(long)]
+    pub loan: Pubkey,
+    /// Principal plus penalty to pay; repays the loan in full when omitted.
+    #[arg(long)]
+    pub amount: Option<u64>,
+}
+
+#[derive(Args)]
+pub struct LiquidateArgs {
+    /// Overdue loan account to liquidate.
+    #[arg(long)]
+    pub loan: Pubkey,
+}
+
+#[derive(Args)]
+pub struct MintArgs {
+    #[arg(long)]
+    pub mint: Pubkey,
+}
+
+pub fn init(ctx: &Context, args: &GovernanceArgs) -> Result<()> {
+    let admin = ctx.signer()?.pubkey();
+    let params = args.apply(GovernanceArgs::init_defaults());
+    let details = BTreeMap::from([("governance", pda::governance(&ctx.program_id).to_string())]);
+    ctx.submit("init", &[instructions::initialize(&ctx.program_id, &admin, params)], details)
+}
+
+pub fn list_collateral(ctx: &Context, args: &ListCollateralArgs) -> Result<()> {
+    let listing_manager = ctx.signer()?.pubkey();
+    let params = CollateralParams {
+        max_borrow_ratio: args.max_borrow_ratio_bps,
+        liquidation_penalty_bps: args.liquidation_penalty_bps,
+        borrow_cap: args.borrow_cap,
+        pyth_feed: args.pyth_feed,
+        max_price_age_secs: args.max_price_age_secs,
+        secondary_oracle: args.secondary_oracle,
+        max_deviation_bps: args.max_deviation_bps,
+        fee_curve: FeeCurve {
+            base_fee_bps: args.base_fee_bps,
+            slope1_bps: args.slope1_bps,
+            slope2_bps: args.slope2_bps,
+            optimal_utilization_bps: args.optimal_utilization_bps,
+            reference_price: args.reference_price,
+        },
+    };
+    params.fee_curve.validate().map_err(|err| anyhow!("invalid fee curve: {err}"))?;
+    let details = BTreeMap::from([
+        ("collateral_config", pda::collateral_config(&ctx.program_id, &args.mint).to_string()),
+        ("flt_mint", pda::flt_mint(&ctx.program_id, &args.mint).to_string()),
+    ]);
+    let ix = instructions::list_collateral(&ctx.program_id, &listing_manager, &args.mint, params);
+    ctx.submit("list-collateral", &[ix], details)
+}
+
+pub fn stake(ctx: &Context, args: &StakeArgs) -> Result<()> {
+    let user = ctx.signer()?.pubkey();
+    let user_token_account = get_associated_token_address(&user, &args.mint);
+    let ix = instructions::stake(&ctx.program_id, &user, &args.mint, &user_token_account, args.amount, args.lock_slots);
+    let details = BTreeMap::from([
+        ("stake_escrow", pda::stake_escrow(&ctx.program_id, &user, &args.mint).to_string()),
+        ("staker", pda::staker(&ctx.program_id, &user, &args.mint).to_string()),
+    ]);
+    ctx.submit("stake", &[ix], details)
+}
+
+pub fn unstake(ctx: &Context, args: &UnstakeArgs) -> Result<()> {
+    let user = ctx.signer()?.pubkey();
+    let user_token_account = get_associated_token_address(&user, &args.mint);
+    let instructions = [
+        create_associated_token_account_idempotent(&user, &user, &args.mint, &anchor_spl::token::ID),
+        instructions::unstake(&ctx.program_id, &user, &args.mint, &user_token_account, args.amount),
+    ];
+    ctx.submit("unstake", &instructions, BTreeMap::new())
+}
+
+pub fn withdraw_stake(ctx: &Context, args: &UnstakeArgs) -> Result<()> {
+    let user = ctx.signer()?.pubkey();
+    let flt_mint = pda::flt_mint(&ctx.program_id, &args.mint);
+    let user_flt_account = get_associated_token_address(&user, &flt_mint);
+    let instructions = [
+        create_associated_token_account_idempotent(&user, &user, &flt_mint, &anchor_spl::token::ID),
+        instructions::withdraw_stake(&ctx.program_id, &user, &args.mint, &user_flt_account, args.amount),
+    ];
+    let details = BTreeMap::from([("flt_account", user_flt_account.to_string())]);
+    ctx.submit("withdraw-stake", &instructions, details)
+}
+
+pub fn redeem(ctx: &Context, args: &UnstakeArgs) -> Result<()> {
+    let user = ctx.signer()?.pubkey();
+    let flt_mint = pda::flt_mint(&ctx.program_id, &args.mint);
+    let user_token_account = get_associated_token_address(&user, &args.mint);
+    let user_flt_account = get_associated_token_address(&user, &flt_mint);
+    let instructions = [
+        create_associated_token_account_idempotent(&user, &user, &args.mint, &anchor_spl::token::ID),
+        instructions::redeem(&ctx.program_id, &user, &args.mint, &user_token_account, &user_flt_account, args.amount),
+    ];
+    ctx.submit("redeem", &instructions, BTreeMap::new())
+}
+
+pub fn borrow(ctx: &Context, args: &BorrowArgs) -> Result<()> {
+    let borrower = ctx.signer()?.pubkey();
+    let config: CollateralConfig = ctx.fetch(&pda::collateral_config(&ctx.program_id, &args.mint))?;
+    let nonce = ctx
+        .fetch_optional::<BorrowerState>(&pda::borrower_state(&ctx.program_id, &borrower))?
+        .map_or(0, |state| state.next_loan_nonce);
+    let borrower_token_account = get_associated_token_address(&borrower, &args.mint);
+    let instructions = [
+        create_associated_token_account_idempotent(&borrower, &borrower, &args.mint, &anchor_spl::token::ID),
+        instructions::borrow(
+            &ctx.program_id,
+            &borrower,
+            &borrower_token_account,
+            &config,
+            nonce,
+            args.amount,
+            args.duration_slots,
+            LoanMode::Term,
+        ),
+    ];
+    let details = BTreeMap::from([
+        ("loan", pda::loan(&ctx.program_id, &borrower, &args.mint, nonce).to_string()),
+        ("nonce", nonce.to_string()),
+    ]);
+    ctx.submit("borrow", &instructions, details)
+}
+
+pub fn repay(ctx: &Context, args: &RepayArgs) -> Result<()> {
+    let borrower = ctx.signer()?.pubkey();
+    let loan: Loan = ctx.fetch(&args.loan)?;
+    ensure!(loan.borrower == borrower, "loan {} belongs to {}", args.loan, loan.borrower);
+    let config: CollateralConfig = ctx.fetch(&pda::collateral_config(&ctx.program_id, &loan.collateral_mint))?;
+    let payoff = quote::payoff_amount(&loan, &config, ctx.client.get_slot()?)?;
+
+    // Paying more than is owed only transfers what is owed, so a full repayment can simply
+    // offer everything and keep up with a penalty that grows until the transaction lands.
+    let amount = args.amount.unwrap_or(u64::MAX);
+    let borrower_token_account = get_associated_token_address(&borrower, &loan.collateral_mint);
+    let ix = instructions::repay(
+        &ctx.program_id,
+        &borrower,
+        &borrower_token_account,
+        &loan.collateral_mint,
+        loan.nonce,
+        amount,
+    );
+    let details = BTreeMap::from([
+        ("loan", args.loan.to_string()),
+        ("principal", loan.amount.to_string()),
+        ("payoff_now", payoff.to_string()),
+    ]);
+    ctx.submit("repay", &[ix], details)
+}
+
+pub fn liquidate(ctx: &Context, args: &LiquidateArgs) -> Result<()> {
+    let liquidator = ctx.signer()?.pubkey();
+    let loan: Loan = ctx.fetch(&args.loan)?;
+    ensure!(loan.active, "loan {} is not active", args.loan);
+    let mint = loan.collateral_mint;
+    let config: CollateralConfig = ctx.fetch(&pda::collateral_config(&ctx.program_id, &mint))?;
+    let staker: Staker = ctx.fetch(&pda::staker(&ctx.program_id, &loan.borrower, &mint))?;
+    let pool_accounts = pda::PoolAccounts::new(&ctx.program_id, &mint);
+    let pool: RewardPool = ctx.fetch(&pool_accounts.reward_pool)?;
+    let vault: TokenAccount = ctx.fetch(&pool_accounts.vault_token)?;
+    let flt_mint: Mint = ctx.fetch(&pool_accounts.flt_mint)?;
+    let outcome = quote::liquidation(&loan, &staker, &config, vault.amount, &pool, flt_mint.supply)?;
+
+    let liquidator_token_account = get_associated_token_address(&liquidator, &mint);
+    let instructions = [
+        create_associated_token_account_idempotent(&liquidator, &liquidator, &mint, &anchor_spl::token::ID),
+        instructions::liquidate(
+            &ctx.program_id,
+            &liquidator,
+            &liquidator_token_account,
+            &loan.borrower,
+            &config,
+            loan.nonce,
+        ),
+    ];
+    let details = BTreeMap::from([
+        ("loan", args.loan.to_string()),
+        ("bonus", outcome.bonus.to_string()),
+        ("seized", outcome.seized.to_string()),
+        ("shortfall", outcome.shortfall.to_string()),
+    ]);
+    ctx.submit("liquidate", &instructions, details)
+}
+
+pub fn compound(ctx: &Context, args: &MintArgs) -> Result<()> {
+    let owner = ctx.signer()?.pubkey();
+    ctx.submit(
+        "compound",
+        &[instructions::compound_rewards(&ctx.program_id, &owner, &args.mint)],
+        BTreeMap::new(),
+    )
+}
+
+pub fn governance_show(ctx: &Context) -> Result<()> {
+    let address = pda::governance(&ctx.program_id);
+    let governance: Governance = ctx.fetch(&address)?;
+    ctx.output.print(&GovernanceView::new(address.to_string(), &governance));
+    Ok(())
+}
+
+/// Apply the change directly while there is no timelock, otherwise queue it.
+pub fn governance_set(ctx: &Context, args: &GovernanceArgs) -> Result<()> {
+    if args.is_empty() {
+        bail!("nothing to change; pass at least one parameter");
+    }
+    let admin = ctx.signer()?.pubkey();
+    let governance: Governance = ctx.fetch(&pda::governance(&ctx.program_id))?;
+    let params = args.apply(governance.params());
+    if governance.timelock_delay_slots == 0 {
+        let ix = instructions::update_governance_parameters(&ctx.program_id, &admin, params);
+        return ctx.submit("governance set", &[ix], BTreeMap::new());
+    }
+
+    let change_id = governance.next_change_id;
+    let ix = instructions::queue_parameter_change(&ctx.program_id, &admin, change_id, ParameterChange::Governance(params));
+    let details = BTreeMap::from([
+        ("pending_change", pda::pending_change(&ctx.program_id, change_id).to_string()),
+        ("timelock_delay_slots", governance.timelock_delay_slots.to_string()),
+    ]);
+    ctx.submit("governance set (queued)", &[ix], details)
+}
+
+pub fn pool_stats(ctx: &Context, args: &MintArgs) -> Result<()> {
+    let pool_accounts = pda::PoolAccounts::new(&ctx.program_id, &args.mint);
+    let config: CollateralConfig = ctx.fetch(&pool_accounts.collateral_config)?;
+    let pool: RewardPool = ctx.fetch(&pool_accounts.reward_pool)?;
+    let vault: TokenAccount = ctx.fetch(&pool_accounts.vault_token)?;
+    let flt_mint: Mint = ctx.fetch(&pool_accounts.flt_mint)?;
+
+    let utilization_bps = quote::utilization_bps(vault.amount, &pool, 0)?;
+    ctx.output.print(&PoolStats {
+        collateral_mint: args.mint.to_string(),
+        status: match config.status {
+            CollateralStatus::Active => "active",
+            CollateralStatus::ReduceOnly => "reduce-only",
+            CollateralStatus::Frozen => "frozen",
+        }
+        .to_string(),
+        vault_balance: vault.amount,
+        vault_assets: quote::vault_assets(vault.amount, &pool)?,
+        flt_supply: flt_mint.supply,
+        exchange_rate: quote::exchange_rate(vault.amount, &pool, flt_mint.supply)?,
+        total_staked: pool.total_staked,
+        active_loan_total: pool.active_loan_total,
+        borrow_cap: config.borrow_cap,
+        utilization_bps,
+        curve_fee_bps: config.fee_curve.fee_bps(utilization_bps)?,
+        accrued_fees: pool.accrued_fees,
+        reserved_rewards: pool.reserved_rewards,
+        insurance_fund: pool.insurance_fund,
+        bad_debt: pool.bad_debt,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn governance_args_override_only_given_fields() {
+        let args = GovernanceArgs { liquidation_grace_slots: Some(42), quorum_bps: Some(2_500), ..Default::default() };
+        assert!(!args.is_empty());
+        assert!(GovernanceArgs::default().is_empty());
+
+        let params = args.apply(GovernanceArgs::init_defaults());
+        assert_eq!(params.liquidation_grace_slots, 42);
+        assert_eq!(params.quorum_bps, 2_500);
+        assert_eq!(params.reward_share_bps, GovernanceArgs::init_defaults().reward_share_bps);
+    }
+
+    #[test]
+    fn secondary_oracle_parses() {
+        let feed = Pubkey::new_unique();
+        assert!(parse_secondary_oracle("none").unwrap() == SecondaryOracle::None);
+        assert!(parse_secondary_oracle("manual").unwrap() == SecondaryOracle::Manual);
+        assert!(parse_secondary_oracle(&format!("pyth:{feed}")).unwrap() == SecondaryOracle::Pyth(feed));
+        assert!(parse_secondary_oracle("pyth:nope").is_err());
+        assert!(parse_secondary_oracle("chainlink").is_err());
+    }
+}
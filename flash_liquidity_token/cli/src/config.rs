@@ -0,0 +1,135 @@
+//
+// Config
+//
+// The `flt` config file: RPC endpoint, program id and signer keypairs.
+//
+//     rpc_url = "https://api.devnet.solana.com"
+//     keypair = "~/.config/solana/id.json"
+//
+//     [keypairs]
+//     admin = "~/keys/flt-admin.json"
+//     liquidator = "~/keys/flt-liquidator.json"
+//
+// `--keypair admin` then signs with the `admin` entry; any other value is read as a path.
+//
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+
+pub const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8899";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub rpc_url: Option<String>,
+    pub program_id: Option<String>,
+    /// Default signer.
+    pub keypair: Option<String>,
+    /// Named signers, selected with `--keypair <name>`.
+    #[serde(default)]
+    pub keypairs: BTreeMap<String, String>,
+}
+
+impl Config {
+    pub fn parse(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    /// Load `path`. A missing file at the default location is an empty config; a missing file
+    /// that was asked for explicitly is an error.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let (path, explicit) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match home_dir() {
+                Some(home) => (home.join(".config/flt/config.toml"), false),
+                None => return Ok(Self::default()),
+            },
+        };
+        match fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents).with_context(|| format!("invalid config {}", path.display())),
+            Err(err) if !explicit && err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("failed to read config {}", path.display())),
+        }
+    }
+
+    /// `--url`, else the config file, else a local validator.
+    pub fn rpc_url(&self, flag: Option<&str>) -> String {
+        flag.or(self.rpc_url.as_deref()).unwrap_or(DEFAULT_RPC_URL).to_string()
+    }
+
+    /// `--program-id`, else the config file, else the program's declared id.
+    pub fn program_id(&self, flag: Option<Pubkey>) -> Result<Pubkey> {
+        match (flag, &self.program_id) {
+            (Some(program_id), _) => Ok(program_id),
+            (None, Some(program_id)) => program_id.parse().map_err(|_| anyhow!("invalid program_id {program_id}")),
+            (None, None) => Ok(flash_liquidity_token::ID),
+        }
+    }
+
+    /// Path of the signer: `--keypair` as a name from `[keypairs]` or a path, else the config
+    /// file's `keypair`, else the Solana CLI default keypair.
+    pub fn keypair_path(&self, flag: Option<&str>) -> Result<PathBuf> {
+        let path = match flag {
+            Some(name_or_path) => self.keypairs.get(name_or_path).map_or(name_or_path, String::as_str),
+            None => match &self.keypair {
+                Some(path) => path.as_str(),
+                None => "~/.config/solana/id.json",
+            },
+        };
+        expand_home(path)
+    }
+
+    pub fn signer(&self, flag: Option<&str>) -> Result<Keypair> {
+        let path = self.keypair_path(flag)?;
+        read_keypair_file(&path).map_err(|err| anyhow!("failed to read keypair {}: {err}", path.display()))
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+fn expand_home(path: &str) -> Result<PathBuf> {
+    match path.strip_prefix("~/") {
+        Some(rest) => Ok(home_dir().ok_or_else(|| anyhow!("HOME is not set; cannot expand {path}"))?.join(rest)),
+        None => Ok(PathBuf::from(path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_keypairs_resolve_before_paths() {
+        let config = Config::parse(
+            r#"
+            rpc_url = "https://rpc.example"
+            keypair = "/keys/default.json"
+
+            [keypairs]
+            admin = "/keys/admin.json"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.rpc_url(None), "https://rpc.example");
+        assert_eq!(config.rpc_url(Some("http://localhost:8899")), "http://localhost:8899");
+        assert_eq!(config.keypair_path(None).unwrap(), PathBuf::from("/keys/default.json"));
+        assert_eq!(config.keypair_path(Some("admin")).unwrap(), PathBuf::from("/keys/admin.json"));
+        assert_eq!(config.keypair_path(Some("./other.json")).unwrap(), PathBuf::from("./other.json"));
+    }
+
+    #[test]
+    fn empty_config_uses_defaults() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config.rpc_url(None), DEFAULT_RPC_URL);
+        assert_eq!(config.program_id(None).unwrap(), flash_liquidity_token::ID);
+        assert!(Config::parse("rpc = \"typo\"").is_err());
+    }
+}
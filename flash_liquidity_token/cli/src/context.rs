@@ -0,0 +1,91 @@
+//
+// Context
+//
+// RPC client, signer and output shared by every subcommand, plus fetching accounts and
+// sending (or only simulating) transactions.
+//
+
+use std::collections::BTreeMap;
+
+use anchor_lang::AccountDeserialize;
+use anyhow::{anyhow, bail, Context as _, Result};
+use flash_liquidity_token_sdk::decode;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+use crate::output::{Output, TxOutcome};
+
+pub struct Context {
+    pub client: RpcClient,
+    pub program_id: Pubkey,
+    pub output: Output,
+    /// Run transactions through simulateTransaction only.
+    pub simulate: bool,
+    /// Loaded only for subcommands that sign.
+    pub signer: Option<Keypair>,
+}
+
+impl Context {
+    pub fn signer(&self) -> Result<&Keypair> {
+        self.signer.as_ref().ok_or_else(|| anyhow!("this command needs a signer"))
+    }
+
+    pub fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        self.fetch_optional(address)?
+            .ok_or_else(|| anyhow!("account {address} does not exist"))
+    }
+
+    pub fn fetch_optional<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>> {
+        let account = self
+            .client
+            .get_account_with_commitment(address, self.client.commitment())
+            .with_context(|| format!("failed to fetch account {address}"))?
+            .value;
+        account
+            .map(|account| decode::account(&account.data).with_context(|| format!("failed to decode account {address}")))
+            .transpose()
+    }
+
+    /// Sign `instructions` with the signer as fee payer and send them, or simulate them with
+    /// `--simulate`. A failed simulation is printed and then returned as an error.
+    pub fn submit(
+        &self,
+        command: &'static str,
+        instructions: &[Instruction],
+        details: BTreeMap<&'static str, String>,
+    ) -> Result<()> {
+        let signer = self.signer()?;
+        let blockhash = self.client.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(instructions, Some(&signer.pubkey()), &[signer], blockhash);
+
+        if self.simulate {
+            let result = self
+                .client
+                .simulate_transaction(&transaction)
+                .with_context(|| format!("{command}: simulateTransaction failed"))?
+                .value;
+            let failed = result.err.is_some();
+            self.output.print(&TxOutcome::Simulated {
+                command,
+                error: result.err.map(|err| err.to_string()),
+                units_consumed: result.units_consumed,
+                logs: result.logs.unwrap_or_default(),
+                details,
+            });
+            if failed {
+                bail!("{command}: simulation failed");
+            }
+            return Ok(());
+        }
+
+        let signature = self
+            .client
+            .send_and_confirm_transaction(&transaction)
+            .with_context(|| format!("{command} failed"))?;
+        self.output.print(&TxOutcome::Sent { command, signature: signature.to_string(), details });
+        Ok(())
+    }
+}
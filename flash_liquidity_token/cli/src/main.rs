@@ -0,0 +1,152 @@
+//! `flt`: operate and use the flash liquidity token program from the command line.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+
+mod commands;
+mod config;
+mod context;
+mod output;
+
+use commands::{
+    BorrowArgs, GovernanceArgs, LiquidateArgs, ListCollateralArgs, MintArgs, RepayArgs, StakeArgs, UnstakeArgs,
+};
+use config::Config;
+use context::Context;
+use output::Output;
+
+/// Operate and use the flash liquidity token program.
+#[derive(Parser)]
+#[command(name = "flt", version)]
+struct Cli {
+    /// Config file [default: ~/.config/flt/config.toml].
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// JSON RPC endpoint, overriding the config file.
+    #[arg(long, global = true)]
+    url: Option<String>,
+    /// Signer: a name from the config file's [keypairs] table, or a keypair file.
+    #[arg(long, global = true)]
+    keypair: Option<String>,
+    /// Program id, overriding the config file.
+    #[arg(long, global = true)]
+    program_id: Option<Pubkey>,
+    /// Print one JSON object instead of human-readable output.
+    #[arg(long, global = true)]
+    json: bool,
+    /// Only run transactions through simulateTransaction; nothing is sent.
+    #[arg(long, global = true)]
+    simulate: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the governance account, with the signer as admin.
+    Init(GovernanceArgs),
+    /// List a collateral and create its vault, FLT mint and reward pool.
+    ListCollateral(ListCollateralArgs),
+    /// Deposit collateral and mint FLT.
+    Stake(StakeArgs),
+    /// Burn staked FLT and withdraw collateral once the lock has expired.
+    Unstake(UnstakeArgs),
+    /// Move staked FLT out of the escrow into the signer's FLT account once the lock has expired.
+    WithdrawStake(UnstakeArgs),
+    /// Burn FLT from the signer's FLT account and withdraw collateral.
+    Redeem(UnstakeArgs),
+    /// Open a term loan against the signer's stake.
+    Borrow(BorrowArgs),
+    /// Repay a loan, in full unless --amount is given.
+    Repay(RepayArgs),
+    /// Liquidate an overdue loan for the liquidation bonus.
+    Liquidate(LiquidateArgs),
+    /// Compound the signer's staking rewards.
+    Compound(MintArgs),
+    /// Show or change governance parameters.
+    #[command(subcommand)]
+    Governance(GovernanceCommand),
+    /// Inspect collateral pools.
+    #[command(subcommand)]
+    Pool(PoolCommand),
+}
+
+#[derive(Subcommand)]
+enum GovernanceCommand {
+    /// Print the governance account.
+    Show,
+    /// Change parameters: applied at once without a timelock, queued otherwise.
+    Set(GovernanceArgs),
+}
+
+#[derive(Subcommand)]
+enum PoolCommand {
+    /// Print a collateral pool's balances, utilization and exchange rate.
+    Stats(MintArgs),
+}
+
+impl Command {
+    fn needs_signer(&self) -> bool {
+        !matches!(self, Self::Governance(GovernanceCommand::Show) | Self::Pool(_))
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let config = Config::load(cli.config.as_deref())?;
+    let signer = match cli.command.needs_signer() {
+        true => Some(config.signer(cli.keypair.as_deref())?),
+        false => None,
+    };
+    let ctx = Context {
+        client: RpcClient::new_with_commitment(config.rpc_url(cli.url.as_deref()), CommitmentConfig::confirmed()),
+        program_id: config.program_id(cli.program_id)?,
+        output: Output { json: cli.json },
+        simulate: cli.simulate,
+        signer,
+    };
+
+    match &cli.command {
+        Command::Init(args) => commands::init(&ctx, args),
+        Command::ListCollateral(args) => commands::list_collateral(&ctx, args),
+        Command::Stake(args) => commands::stake(&ctx, args),
+        Command::Unstake(args) => commands::unstake(&ctx, args),
+        Command::WithdrawStake(args) => commands::withdraw_stake(&ctx, args),
+        Command::Redeem(args) => commands::redeem(&ctx, args),
+        Command::Borrow(args) => commands::borrow(&ctx, args),
+        Command::Repay(args) => commands::repay(&ctx, args),
+        Command::Liquidate(args) => commands::liquidate(&ctx, args),
+        Command::Compound(args) => commands::compound(&ctx, args),
+        Command::Governance(GovernanceCommand::Show) => commands::governance_show(&ctx),
+        Command::Governance(GovernanceCommand::Set(args)) => commands::governance_set(&ctx, args),
+        Command::Pool(PoolCommand::Stats(args)) => commands::pool_stats(&ctx, args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn global_flags_follow_subcommands() {
+        let cli = Cli::try_parse_from(["flt", "pool", "stats", "--mint", &Pubkey::new_unique().to_string(), "--json"])
+            .unwrap();
+        assert!(cli.json);
+        assert!(!cli.command.needs_signer());
+
+        let cli = Cli::try_parse_from(["flt", "--simulate", "governance", "set", "--quorum-bps", "2000"]).unwrap();
+        assert!(cli.simulate);
+        assert!(cli.command.needs_signer());
+    }
+}
@@ -0,0 +1,236 @@
+//
+// Output
+//
+// Everything `flt` prints, as human-readable text or as one JSON object per command (`--json`).
+//
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use flash_liquidity_token::{
+    Governance, PAUSE_BORROW, PAUSE_COMPOUND, PAUSE_LIQUIDATE, PAUSE_REPAY, PAUSE_STAKE, PAUSE_UNSTAKE,
+};
+use serde::Serialize;
+
+pub struct Output {
+    pub json: bool,
+}
+
+impl Output {
+    pub fn print<T: Serialize + fmt::Display>(&self, value: &T) {
+        if self.json {
+            println!("{}", serde_json::to_string(value).expect("output serializes to JSON"));
+        } else {
+            println!("{value}");
+        }
+    }
+}
+
+fn write_details(f: &mut fmt::Formatter, details: &BTreeMap<&'static str, String>) -> fmt::Result {
+    for (key, value) in details {
+        write!(f, "\n  {key}: {value}")?;
+    }
+    Ok(())
+}
+
+/// Result of a transaction-sending command.
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TxOutcome {
+    Sent {
+        command: &'static str,
+        signature: String,
+        details: BTreeMap<&'static str, String>,
+    },
+    /// `--simulate`: the transaction was only run through simulateTransaction.
+    Simulated {
+        command: &'static str,
+        error: Option<String>,
+        units_consumed: Option<u64>,
+        logs: Vec<String>,
+        details: BTreeMap<&'static str, String>,
+    },
+}
+
+impl fmt::Display for TxOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Sent { command, signature, details } => {
+                write!(f, "{command}: confirmed {signature}")?;
+                write_details(f, details)
+            }
+            Self::Simulated { command, error, units_consumed, logs, details } => {
+                match error {
+                    Some(error) => write!(f, "{command}: simulation failed: {error}")?,
+                    None => write!(f, "{command}: simulation succeeded")?,
+                }
+                if let Some(units) = units_consumed {
+                    write!(f, " ({units} compute units)")?;
+                }
+                write_details(f, details)?;
+                for log in logs {
+                    write!(f, "\n  | {log}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Names of the instruction families paused in `flags`.
+pub fn paused_families(flags: u8) -> Vec<&'static str> {
+    [
+        (PAUSE_STAKE, "stake"),
+        (PAUSE_UNSTAKE, "unstake"),
+        (PAUSE_BORROW, "borrow"),
+        (PAUSE_REPAY, "repay"),
+        (PAUSE_LIQUIDATE, "liquidate"),
+        (PAUSE_COMPOUND, "compound"),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| flags & flag != 0)
+    .map(|(_, name)| name)
+    .collect()
+}
+
+/// `governance show`.
+#[derive(Debug, Serialize)]
+pub struct GovernanceView {
+    pub address: String,
+    pub admin: String,
+    pub pending_admin: String,
+    pub fee_manager: String,
+    pub risk_manager: String,
+    pub listing_manager: String,
+    pub guardian: String,
+    pub liquidation_grace_slots: u64,
+    pub compound_rate_numerator: u64,
+    pub compound_rate_denominator: u64,
+    pub reward_share_bps: u64,
+    pub insurance_share_bps: u64,
+    pub timelock_delay_slots: u64,
+    pub vote_collateral_mint: String,
+    pub voting_period_slots: u64,
+    pub quorum_bps: u64,
+    pub approval_bps: u64,
+    pub next_change_id: u64,
+    pub next_proposal_id: u64,
+    pub paused: Vec<&'static str>,
+}
+
+impl GovernanceView {
+    pub fn new(address: String, governance: &Governance) -> Self {
+        Self {
+            address,
+            admin: governance.admin.to_string(),
+            pending_admin: governance.pending_admin.to_string(),
+            fee_manager: governance.roles.fee_manager.to_string(),
+            risk_manager: governance.roles.risk_manager.to_string(),
+            listing_manager: governance.roles.listing_manager.to_string(),
+            guardian: governance.roles.guardian.to_string(),
+            liquidation_grace_slots: governance.liquidation_grace_slots,
+            compound_rate_numerator: governance.compound_rate_numerator,
+            compound_rate_denominator: governance.compound_rate_denominator,
+            reward_share_bps: governance.reward_share_bps,
+            insurance_share_bps: governance.insurance_share_bps,
+            timelock_delay_slots: governance.timelock_delay_slots,
+            vote_collateral_mint: governance.vote_collateral_mint.to_string(),
+            voting_period_slots: governance.voting_period_slots,
+            quorum_bps: governance.quorum_bps,
+            approval_bps: governance.approval_bps,
+            next_change_id: governance.next_change_id,
+            next_proposal_id: governance.next_proposal_id,
+            paused: paused_families(governance.paused),
+        }
+    }
+}
+
+impl fmt::Display for GovernanceView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let paused = if self.paused.is_empty() { "none".to_string() } else { self.paused.join(", ") };
+        writeln!(f, "Governance {}", self.address)?;
+        writeln!(f, "  admin:                   {}", self.admin)?;
+        writeln!(f, "  pending admin:           {}", self.pending_admin)?;
+        writeln!(f, "  fee manager:             {}", self.fee_manager)?;
+        writeln!(f, "  risk manager:            {}", self.risk_manager)?;
+        writeln!(f, "  listing manager:         {}", self.listing_manager)?;
+        writeln!(f, "  guardian:                {}", self.guardian)?;
+        writeln!(f, "  liquidation grace slots: {}", self.liquidation_grace_slots)?;
+        writeln!(f, "  compound rate:           {}/{}", self.compound_rate_numerator, self.compound_rate_denominator)?;
+        writeln!(f, "  reward share:            {} bps", self.reward_share_bps)?;
+        writeln!(f, "  insurance share:         {} bps", self.insurance_share_bps)?;
+        writeln!(f, "  timelock delay slots:    {}", self.timelock_delay_slots)?;
+        writeln!(f, "  vote collateral mint:    {}", self.vote_collateral_mint)?;
+        writeln!(f, "  voting period slots:     {}", self.voting_period_slots)?;
+        writeln!(f, "  quorum:                  {} bps", self.quorum_bps)?;
+        writeln!(f, "  approval:                {} bps", self.approval_bps)?;
+        writeln!(f, "  next change id:          {}", self.next_change_id)?;
+        writeln!(f, "  next proposal id:        {}", self.next_proposal_id)?;
+        write!(f, "  paused:                  {paused}")
+    }
+}
+
+/// `pool stats`.
+#[derive(Debug, Serialize)]
+pub struct PoolStats {
+    pub collateral_mint: String,
+    pub status: String,
+    pub vault_balance: u64,
+    pub vault_assets: u64,
+    pub flt_supply: u64,
+    pub exchange_rate: u64,                // collateral per FLT, scaled by EXCHANGE_RATE_SCALE
+    pub total_staked: u64,                 // reward weight
+    pub active_loan_total: u64,
+    pub borrow_cap: u64,
+    pub utilization_bps: u64,
+    pub curve_fee_bps: u64,                // fee curve at the current utilization, before the oracle adjustment
+    pub accrued_fees: u64,
+    pub reserved_rewards: u64,
+    pub insurance_fund: u64,
+    pub bad_debt: u64,
+}
+
+impl fmt::Display for PoolStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rate = self.exchange_rate as f64 / flash_liquidity_token::EXCHANGE_RATE_SCALE as f64;
+        writeln!(f, "Pool {} ({})", self.collateral_mint, self.status)?;
+        writeln!(f, "  vault balance:     {}", self.vault_balance)?;
+        writeln!(f, "  vault assets:      {}", self.vault_assets)?;
+        writeln!(f, "  FLT supply:        {}", self.flt_supply)?;
+        writeln!(f, "  exchange rate:     {rate:.9} collateral per FLT")?;
+        writeln!(f, "  reward weight:     {}", self.total_staked)?;
+        writeln!(f, "  active loans:      {} (cap {})", self.active_loan_total, self.borrow_cap)?;
+        writeln!(f, "  utilization:       {} bps", self.utilization_bps)?;
+        writeln!(f, "  curve fee:         {} bps before oracle adjustment", self.curve_fee_bps)?;
+        writeln!(f, "  accrued fees:      {}", self.accrued_fees)?;
+        writeln!(f, "  reserved rewards:  {}", self.reserved_rewards)?;
+        writeln!(f, "  insurance fund:    {}", self.insurance_fund)?;
+        write!(f, "  bad debt:          {}", self.bad_debt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulated_outcome_is_tagged() {
+        let outcome = TxOutcome::Simulated {
+            command: "stake",
+            error: None,
+            units_consumed: Some(42_000),
+            logs: vec!["Program log: Instruction: Stake".to_string()],
+            details: BTreeMap::new(),
+        };
+        let json: serde_json::Value = serde_json::to_value(&outcome).unwrap();
+        assert_eq!(json["status"], "simulated");
+        assert_eq!(json["units_consumed"], 42_000);
+        assert!(outcome.to_string().starts_with("stake: simulation succeeded (42000 compute units)"));
+    }
+
+    #[test]
+    fn paused_flags_are_named() {
+        assert_eq!(paused_families(PAUSE_BORROW | PAUSE_COMPOUND), vec!["borrow", "compound"]);
+        assert!(paused_families(0).is_empty());
+    }
+}
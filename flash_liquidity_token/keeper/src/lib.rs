@@ -0,0 +1,95 @@
+//! Off-chain liquidation keeper for the flash liquidity token program.
+//!
+//! Each pass fetches active `Loan` accounts, picks those past
+//! `due_slot + liquidation_grace_slots` and submits `liquidate` for each of them.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use anyhow::Result;
+use flash_liquidity_token::CollateralConfig;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+pub mod liquidator;
+pub mod report;
+pub mod scanner;
+
+use liquidator::{liquidate_instructions, send_with_retry, RetryPolicy};
+use report::{KeeperEvent, Reporter};
+use scanner::LoanEntry;
+
+/// How a keeper pass behaves.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KeeperOptions {
+    /// Report liquidatable loans without submitting transactions.
+    pub dry_run: bool,
+    pub retry: RetryPolicy,
+}
+
+/// Run one scan-and-liquidate pass and return the loans found liquidatable.
+/// Failures to liquidate a single loan are reported and do not abort the pass.
+pub fn run_once(
+    client: &RpcClient,
+    liquidator: &Keypair,
+    program_id: &Pubkey,
+    options: &KeeperOptions,
+    reporter: &Reporter,
+) -> Result<Vec<LoanEntry>> {
+    let governance = scanner::fetch_governance(client, program_id)?;
+    let loans = scanner::fetch_active_loans(client, program_id)?;
+    let slot = client.get_slot()?;
+    let active_loans = loans.len();
+    let due = scanner::liquidatable(loans, governance.liquidation_grace_slots, slot);
+    reporter.emit(&KeeperEvent::Scan { slot, active_loans, liquidatable: due.len() });
+
+    let mut configs: HashMap<Pubkey, CollateralConfig> = HashMap::new();
+    for entry in &due {
+        let loan = entry.address.to_string();
+        reporter.emit(&KeeperEvent::Liquidatable {
+            loan: loan.clone(),
+            borrower: entry.loan.borrower.to_string(),
+            collateral_mint: entry.loan.collateral_mint.to_string(),
+            amount: entry.loan.amount,
+            due_slot: entry.loan.due_slot,
+            slot,
+        });
+        if options.dry_run {
+            reporter.emit(&KeeperEvent::Skipped { loan });
+            continue;
+        }
+
+        let mint = entry.loan.collateral_mint;
+        let config = match configs.entry(mint) {
+            Entry::Occupied(cached) => cached.into_mut(),
+            Entry::Vacant(slot) => match scanner::fetch_collateral_config(client, program_id, &mint) {
+                Ok(config) => slot.insert(config),
+                Err(err) => {
+                    reporter.emit(&KeeperEvent::Failed { loan: Some(loan), error: format!("{err:#}") });
+                    continue;
+                }
+            },
+        };
+
+        let instructions = liquidate_instructions(program_id, &liquidator.pubkey(), &entry.loan, config);
+        let result = send_with_retry(client, liquidator, &instructions, &options.retry, |attempt, err, backoff| {
+            reporter.emit(&KeeperEvent::Retry {
+                loan: loan.clone(),
+                attempt,
+                backoff_ms: backoff.as_millis() as u64,
+                error: err.to_string(),
+            });
+        });
+        match result {
+            Ok((signature, attempts)) => reporter.emit(&KeeperEvent::Liquidated {
+                loan,
+                signature: signature.to_string(),
+                attempts,
+            }),
+            Err(err) => reporter.emit(&KeeperEvent::Failed { loan: Some(loan), error: err.to_string() }),
+        }
+    }
+
+    Ok(due)
+}
@@ -0,0 +1,129 @@
+//
+// Liquidator
+//
+// Builds `liquidate` transactions and submits them with retry and exponential backoff.
+//
+
+use std::thread;
+use std::time::Duration;
+
+use flash_liquidity_token::{CollateralConfig, Loan};
+use flash_liquidity_token_sdk::instructions;
+use solana_client::client_error::ClientError;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+/// How often, and how patiently, a failed submission is retried.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retrying after failed attempt number `attempt` (1-based).
+    /// Doubles from `initial_backoff` and is capped at `max_backoff`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+/// Instructions liquidating `loan`: create the liquidator's collateral token account if it is
+/// missing, then `liquidate`. The bonus is paid to the liquidator's associated token account.
+pub fn liquidate_instructions(
+    program_id: &Pubkey,
+    liquidator: &Pubkey,
+    loan: &Loan,
+    config: &CollateralConfig,
+) -> Vec<Instruction> {
+    let mint = loan.collateral_mint;
+    let liquidator_token_account = get_associated_token_address(liquidator, &mint);
+    vec![
+        create_associated_token_account_idempotent(liquidator, liquidator, &mint, &anchor_spl::token::ID),
+        instructions::liquidate(program_id, liquidator, &liquidator_token_account, &loan.borrower, config, loan.nonce),
+    ]
+}
+
+/// Whether a failed submission is worth retrying.
+/// Program errors (the loan was repaid or liquidated by someone else, the oracle is stale, ...)
+/// are final for this scan; RPC, network and blockhash failures are retried.
+pub fn is_retryable(err: &ClientError) -> bool {
+    !matches!(
+        err.get_transaction_error(),
+        Some(TransactionError::InstructionError(_, InstructionError::Custom(_)))
+    )
+}
+
+/// Sign and send `instructions` with a fresh blockhash per attempt.
+/// `on_retry` is called with the failed attempt number, its error and the backoff before the
+/// next attempt. Returns the signature and the number of attempts used.
+#[allow(clippy::result_large_err)]
+pub fn send_with_retry(
+    client: &RpcClient,
+    payer: &Keypair,
+    instructions: &[Instruction],
+    policy: &RetryPolicy,
+    mut on_retry: impl FnMut(u32, &ClientError, Duration),
+) -> Result<(Signature, u32), ClientError> {
+    let mut attempt = 1;
+    loop {
+        let result = client.get_latest_blockhash().and_then(|blockhash| {
+            let transaction =
+                Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &[payer], blockhash);
+            client.send_and_confirm_transaction(&transaction)
+        });
+        match result {
+            Ok(signature) => return Ok((signature, attempt)),
+            Err(err) if attempt < policy.max_attempts && is_retryable(&err) => {
+                let backoff = policy.backoff(attempt);
+                on_retry(attempt, &err, backoff);
+                thread::sleep(backoff);
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1_000),
+        };
+        let delays: Vec<u128> = (1..=6).map(|attempt| policy.backoff(attempt).as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1_000, 1_000]);
+        assert_eq!(policy.backoff(64), policy.max_backoff);
+    }
+
+    #[test]
+    fn program_errors_are_not_retried() {
+        let program_error: ClientError =
+            TransactionError::InstructionError(1, InstructionError::Custom(6008)).into();
+        assert!(!is_retryable(&program_error));
+        let blockhash_error: ClientError = TransactionError::BlockhashNotFound.into();
+        assert!(is_retryable(&blockhash_error));
+    }
+}
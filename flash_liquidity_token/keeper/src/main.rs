@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use flash_liquidity_token_keeper::liquidator::RetryPolicy;
+use flash_liquidity_token_keeper::report::{KeeperEvent, Reporter};
+use flash_liquidity_token_keeper::{run_once, KeeperOptions};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::read_keypair_file;
+
+/// Liquidates overdue flash liquidity token loans.
+#[derive(Parser, Debug)]
+#[command(name = "flt-keeper", version)]
+struct Args {
+    /// JSON RPC endpoint.
+    #[arg(long, default_value = "http://127.0.0.1:8899")]
+    rpc_url: String,
+    /// Keypair paying for and signing liquidations; also receives the liquidation bonus.
+    /// Defaults to the Solana CLI keypair.
+    #[arg(long)]
+    keypair: Option<PathBuf>,
+    /// Program to watch.
+    #[arg(long, default_value_t = flash_liquidity_token::ID)]
+    program_id: Pubkey,
+    /// Seconds between scans.
+    #[arg(long, default_value_t = 5)]
+    poll_interval_secs: u64,
+    /// Attempts per liquidation before giving up until the next scan.
+    #[arg(long, default_value_t = 5)]
+    max_attempts: u32,
+    /// Backoff after the first failed attempt, doubled after each further failure.
+    #[arg(long, default_value_t = 500)]
+    initial_backoff_ms: u64,
+    /// Upper bound on the backoff between attempts.
+    #[arg(long, default_value_t = 8_000)]
+    max_backoff_ms: u64,
+    /// Report liquidatable loans without submitting transactions.
+    #[arg(long)]
+    dry_run: bool,
+    /// Print one JSON object per line instead of human-readable logs.
+    #[arg(long)]
+    json: bool,
+    /// Run a single pass and exit.
+    #[arg(long)]
+    once: bool,
+}
+
+fn default_keypair_path() -> Result<PathBuf> {
+    let home = std::env::var_os("HOME").ok_or_else(|| anyhow!("HOME is not set; pass --keypair"))?;
+    Ok(PathBuf::from(home).join(".config/solana/id.json"))
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let keypair_path = match args.keypair {
+        Some(path) => path,
+        None => default_keypair_path()?,
+    };
+    let liquidator = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow!("failed to read keypair {}: {err}", keypair_path.display()))?;
+    let client = RpcClient::new_with_commitment(args.rpc_url, CommitmentConfig::confirmed());
+    let reporter = Reporter { json: args.json };
+    let options = KeeperOptions {
+        dry_run: args.dry_run,
+        retry: RetryPolicy {
+            max_attempts: args.max_attempts.max(1),
+            initial_backoff: Duration::from_millis(args.initial_backoff_ms),
+            max_backoff: Duration::from_millis(args.max_backoff_ms),
+        },
+    };
+
+    loop {
+        match run_once(&client, &liquidator, &args.program_id, &options, &reporter) {
+            Ok(_) => {}
+            // A single pass surfaces scan failures; the daemon logs them and tries again.
+            Err(err) if !args.once => reporter.emit(&KeeperEvent::Failed { loan: None, error: format!("{err:#}") }),
+            Err(err) => return Err(err),
+        }
+        if args.once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(args.poll_interval_secs));
+    }
+}
@@ -0,0 +1,100 @@
+//
+// Report
+//
+// Keeper events, printed as human-readable lines or as one JSON object per line.
+//
+
+use std::fmt;
+
+use serde::Serialize;
+
+/// Something the keeper did or observed.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum KeeperEvent {
+    /// A scan of active loans finished.
+    Scan {
+        slot: u64,
+        active_loans: usize,
+        liquidatable: usize,
+    },
+    /// A loan is past its grace period.
+    Liquidatable {
+        loan: String,
+        borrower: String,
+        collateral_mint: String,
+        amount: u64,
+        due_slot: u64,
+        slot: u64,
+    },
+    /// A dry run skipped submitting a liquidation.
+    Skipped { loan: String },
+    /// A liquidation was confirmed.
+    Liquidated {
+        loan: String,
+        signature: String,
+        attempts: u32,
+    },
+    /// A submission failed and will be retried.
+    Retry {
+        loan: String,
+        attempt: u32,
+        backoff_ms: u64,
+        error: String,
+    },
+    /// A liquidation gave up, or a scan failed.
+    Failed { loan: Option<String>, error: String },
+}
+
+impl fmt::Display for KeeperEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scan { slot, active_loans, liquidatable } => {
+                write!(f, "slot {slot}: {active_loans} active loans, {liquidatable} liquidatable")
+            }
+            Self::Liquidatable { loan, borrower, amount, due_slot, .. } => {
+                write!(f, "loan {loan} of {borrower} is liquidatable ({amount} due at slot {due_slot})")
+            }
+            Self::Skipped { loan } => write!(f, "dry run: not liquidating {loan}"),
+            Self::Liquidated { loan, signature, attempts } => {
+                write!(f, "liquidated {loan} in {attempts} attempt(s): {signature}")
+            }
+            Self::Retry { loan, attempt, backoff_ms, error } => {
+                write!(f, "attempt {attempt} for {loan} failed, retrying in {backoff_ms}ms: {error}")
+            }
+            Self::Failed { loan: Some(loan), error } => write!(f, "giving up on {loan}: {error}"),
+            Self::Failed { loan: None, error } => write!(f, "scan failed: {error}"),
+        }
+    }
+}
+
+/// Prints keeper events to stdout.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Reporter {
+    pub json: bool,
+}
+
+impl Reporter {
+    pub fn emit(&self, event: &KeeperEvent) {
+        if self.json {
+            println!("{}", serde_json::to_string(event).expect("keeper events serialize"));
+        } else {
+            println!("{event}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_lines_are_tagged() {
+        let event = KeeperEvent::Scan { slot: 42, active_loans: 3, liquidatable: 1 };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"scan","slot":42,"active_loans":3,"liquidatable":1}"#
+        );
+        assert_eq!(event.to_string(), "slot 42: 3 active loans, 1 liquidatable");
+    }
+}
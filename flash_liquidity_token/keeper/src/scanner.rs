@@ -0,0 +1,135 @@
+//
+// Scanner
+//
+// Finds active loans with getProgramAccounts and picks out the ones past their grace period.
+//
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{Context, Result};
+use flash_liquidity_token::{CollateralConfig, Governance, Loan};
+use flash_liquidity_token_sdk::{decode, pda};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+
+/// Offset of `Loan.active`: discriminator, borrower, collateral mint, then four u64 fields.
+pub const LOAN_ACTIVE_OFFSET: usize = 8 + 32 + 32 + (4 * 8);
+
+/// An active loan and its address.
+#[derive(Clone)]
+pub struct LoanEntry {
+    pub address: Pubkey,
+    pub loan: Loan,
+}
+
+/// Fetch and decode an Anchor account.
+pub fn fetch_account<T: AccountDeserialize>(client: &RpcClient, address: &Pubkey) -> Result<T> {
+    let data = client
+        .get_account_data(address)
+        .with_context(|| format!("failed to fetch account {address}"))?;
+    decode::account(&data).with_context(|| format!("failed to decode account {address}"))
+}
+
+pub fn fetch_governance(client: &RpcClient, program_id: &Pubkey) -> Result<Governance> {
+    fetch_account(client, &pda::governance(program_id))
+}
+
+pub fn fetch_collateral_config(client: &RpcClient, program_id: &Pubkey, collateral_mint: &Pubkey) -> Result<CollateralConfig> {
+    fetch_account(client, &pda::collateral_config(program_id, collateral_mint))
+}
+
+/// All active `Loan` accounts of the program.
+/// Filters on account size, the `Loan` discriminator and `active == true`, so closed or
+/// liquidated loans are never downloaded.
+pub fn fetch_active_loans(client: &RpcClient, program_id: &Pubkey) -> Result<Vec<LoanEntry>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(Loan::LEN as u64),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, Loan::DISCRIMINATOR.to_vec())),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(LOAN_ACTIVE_OFFSET, vec![1])),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(client.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = client
+        .get_program_accounts_with_config(program_id, config)
+        .context("getProgramAccounts for loans failed")?;
+
+    accounts
+        .into_iter()
+        .map(|(address, account)| {
+            let loan = decode::loan(&account.data).with_context(|| format!("failed to decode loan {address}"))?;
+            Ok(LoanEntry { address, loan })
+        })
+        .collect()
+}
+
+/// Whether `liquidate` will accept the loan at `current_slot`.
+/// Mirrors the on-chain check: strictly after `due_slot + liquidation_grace_slots`.
+pub fn is_liquidatable(loan: &Loan, liquidation_grace_slots: u64, current_slot: u64) -> bool {
+    loan.active && current_slot > loan.due_slot.saturating_add(liquidation_grace_slots)
+}
+
+/// The loans in `loans` that can be liquidated at `current_slot`, oldest due slot first.
+pub fn liquidatable(loans: Vec<LoanEntry>, liquidation_grace_slots: u64, current_slot: u64) -> Vec<LoanEntry> {
+    let mut due: Vec<LoanEntry> = loans
+        .into_iter()
+        .filter(|entry| is_liquidatable(&entry.loan, liquidation_grace_slots, current_slot))
+        .collect();
+    due.sort_by_key(|entry| entry.loan.due_slot);
+    due
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AccountSerialize;
+    use flash_liquidity_token::LoanMode;
+
+    fn loan(due_slot: u64, active: bool) -> LoanEntry {
+        LoanEntry {
+            address: Pubkey::new_unique(),
+            loan: Loan {
+                borrower: Pubkey::new_unique(),
+                collateral_mint: Pubkey::new_unique(),
+                nonce: 0,
+                amount: 1_000,
+                start_slot: 0,
+                due_slot,
+                active,
+                mode: LoanMode::Term,
+                bump: 255,
+            },
+        }
+    }
+
+    #[test]
+    fn active_offset_points_at_active_flag() {
+        let entry = loan(7, true);
+        let mut data = Vec::new();
+        entry.loan.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), Loan::LEN);
+        assert_eq!(data[LOAN_ACTIVE_OFFSET], 1);
+    }
+
+    #[test]
+    fn liquidatable_only_after_grace_period() {
+        let entry = loan(100, true);
+        assert!(!is_liquidatable(&entry.loan, 10, 110));
+        assert!(is_liquidatable(&entry.loan, 10, 111));
+        assert!(!is_liquidatable(&loan(100, false).loan, 10, 1_000));
+        assert!(!is_liquidatable(&loan(u64::MAX, true).loan, 10, u64::MAX));
+    }
+
+    #[test]
+    fn liquidatable_sorts_oldest_first() {
+        let due = liquidatable(vec![loan(50, true), loan(10, true), loan(500, true)], 0, 100);
+        assert_eq!(due.iter().map(|entry| entry.loan.due_slot).collect::<Vec<_>>(), vec![10, 50]);
+    }
+}
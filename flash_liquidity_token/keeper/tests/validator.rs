@@ -0,0 +1,281 @@
+//! Runs the keeper against a local `solana-test-validator` with the program deployed.
+//!
+//! Needs `solana-test-validator` on `PATH` and a built program (`anchor build`, or set
+//! `FLT_PROGRAM_SO`). Run with `cargo test -- --ignored`.
+
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anchor_spl::token::{spl_token, Mint, TokenAccount};
+use flash_liquidity_token::{
+    CollateralConfig, CollateralParams, FeeCurve, GovernanceParams, LoanMode, RewardPool, SecondaryOracle, Staker,
+};
+use flash_liquidity_token_keeper::report::Reporter;
+use flash_liquidity_token_keeper::scanner::{fetch_account, fetch_active_loans, fetch_governance};
+use flash_liquidity_token_keeper::{run_once, KeeperOptions};
+use flash_liquidity_token_sdk::{instructions, pda, quote};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account;
+
+/// A `solana-test-validator` process, killed on drop.
+struct TestValidator {
+    process: Child,
+    ledger: PathBuf,
+    rpc_url: String,
+}
+
+impl TestValidator {
+    fn start() -> Self {
+        let program_so = std::env::var_os("FLT_PROGRAM_SO")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/flash_liquidity_token.so"));
+        assert!(program_so.exists(), "program not built: {}", program_so.display());
+
+        let rpc_port = free_port();
+        let ledger = std::env::temp_dir().join(format!("flt-keeper-ledger-{}", std::process::id()));
+        let process = Command::new("solana-test-validator")
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(&ledger)
+            .arg("--rpc-port")
+            .arg(rpc_port.to_string())
+            .arg("--faucet-port")
+            .arg(free_port().to_string())
+            .arg("--bpf-program")
+            .arg(flash_liquidity_token::ID.to_string())
+            .arg(&program_so)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start solana-test-validator");
+
+        let validator = Self { process, ledger, rpc_url: format!("http://127.0.0.1:{rpc_port}") };
+        let client = validator.client();
+        let deadline = Instant::now() + Duration::from_secs(60);
+        while client.get_health().is_err() {
+            assert!(Instant::now() < deadline, "solana-test-validator did not become healthy");
+            thread::sleep(Duration::from_millis(250));
+        }
+        validator
+    }
+
+    fn client(&self) -> RpcClient {
+        RpcClient::new_with_commitment(self.rpc_url.clone(), CommitmentConfig::confirmed())
+    }
+}
+
+impl Drop for TestValidator {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        let _ = std::fs::remove_dir_all(&self.ledger);
+    }
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+fn funded_keypair(client: &RpcClient) -> Keypair {
+    let keypair = Keypair::new();
+    let signature = client.request_airdrop(&keypair.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while !client.confirm_transaction(&signature).unwrap() {
+        assert!(Instant::now() < deadline, "airdrop was not confirmed");
+        thread::sleep(Duration::from_millis(250));
+    }
+    keypair
+}
+
+fn send(client: &RpcClient, payer: &Keypair, signers: &[&Keypair], instructions: &[Instruction]) {
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &all_signers,
+        client.get_latest_blockhash().unwrap(),
+    );
+    client.send_and_confirm_transaction(&transaction).unwrap();
+}
+
+fn initialize(client: &RpcClient, admin: &Keypair) {
+    let params = GovernanceParams {
+        liquidation_grace_slots: 0,
+        compound_rate_numerator: 1,
+        compound_rate_denominator: 100,
+        reward_share_bps: 5_000,
+        insurance_share_bps: 1_000,
+        timelock_delay_slots: 0,
+        vote_collateral_mint: Pubkey::default(),
+        voting_period_slots: 100,
+        quorum_bps: 1_000,
+        approval_bps: 5_000,
+    };
+    send(client, admin, &[], &[instructions::initialize(&flash_liquidity_token::ID, &admin.pubkey(), params)]);
+}
+
+/// Create a collateral mint and list it. The Pyth feed is an empty account, so pricing falls
+/// back to the manual price the admin (as risk manager) pushes.
+fn list_collateral(client: &RpcClient, admin: &Keypair) -> CollateralConfig {
+    let program_id = flash_liquidity_token::ID;
+    let mint = Keypair::new();
+    let space = spl_token::state::Mint::LEN;
+    let rent = client.get_minimum_balance_for_rent_exemption(space).unwrap();
+    send(
+        client,
+        admin,
+        &[&mint],
+        &[
+            system_instruction::create_account(&admin.pubkey(), &mint.pubkey(), rent, space as u64, &spl_token::ID),
+            spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), &admin.pubkey(), None, 6).unwrap(),
+        ],
+    );
+
+    let params = CollateralParams {
+        max_borrow_ratio: 5_000,
+        liquidation_penalty_bps: 100,
+        borrow_cap: u64::MAX,
+        pyth_feed: Pubkey::new_unique(),
+        max_price_age_secs: 600,
+        secondary_oracle: SecondaryOracle::Manual,
+        max_deviation_bps: 500,
+        fee_curve: FeeCurve {
+            base_fee_bps: 5,
+            slope1_bps: 20,
+            slope2_bps: 200,
+            optimal_utilization_bps: 8_000,
+            reference_price: 0,
+        },
+    };
+    send(
+        client,
+        admin,
+        &[],
+        &[
+            instructions::list_collateral(&program_id, &admin.pubkey(), &mint.pubkey(), params),
+            instructions::set_manual_price(&program_id, &admin.pubkey(), &mint.pubkey(), 100_000_000_000),
+        ],
+    );
+    fetch_account(client, &pda::collateral_config(&program_id, &mint.pubkey())).unwrap()
+}
+
+#[test]
+#[ignore = "requires solana-test-validator and a built program"]
+fn keeper_liquidates_overdue_loan() {
+    let program_id = flash_liquidity_token::ID;
+    let validator = TestValidator::start();
+    let client = validator.client();
+    let admin = funded_keypair(&client);
+    let borrower = funded_keypair(&client);
+    let keeper = funded_keypair(&client);
+    initialize(&client, &admin);
+    let config = list_collateral(&client, &admin);
+    let mint = config.collateral_mint;
+
+    // The borrower stakes 100 tokens and opens a term loan that is due at once.
+    let borrower_token_account = get_associated_token_address(&borrower.pubkey(), &mint);
+    send(
+        &client,
+        &admin,
+        &[],
+        &[
+            create_associated_token_account(&admin.pubkey(), &borrower.pubkey(), &mint, &spl_token::ID),
+            spl_token::instruction::mint_to(
+                &spl_token::ID,
+                &mint,
+                &borrower_token_account,
+                &admin.pubkey(),
+                &[],
+                100_000_000,
+            )
+            .unwrap(),
+        ],
+    );
+    send(
+        &client,
+        &borrower,
+        &[],
+        &[instructions::stake(&program_id, &borrower.pubkey(), &mint, &borrower_token_account, 100_000_000, 0)],
+    );
+    send(
+        &client,
+        &borrower,
+        &[],
+        &[instructions::borrow(
+            &program_id,
+            &borrower.pubkey(),
+            &borrower_token_account,
+            &config,
+            0,
+            10_000_000,
+            0,
+            LoanMode::Term,
+        )],
+    );
+    let loan_address = pda::loan(&program_id, &borrower.pubkey(), &mint, 0);
+
+    let governance = fetch_governance(&client, &program_id).unwrap();
+    assert_eq!(governance.liquidation_grace_slots, 0);
+    let loans = fetch_active_loans(&client, &program_id).unwrap();
+    assert_eq!(loans.len(), 1);
+    assert_eq!(loans[0].address, loan_address);
+    let due_slot = loans[0].loan.due_slot;
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while client.get_slot().unwrap() <= due_slot {
+        assert!(Instant::now() < deadline, "validator did not advance past the due slot");
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    // A dry run reports the overdue loan and leaves it open.
+    let reporter = Reporter { json: true };
+    let dry_run = KeeperOptions { dry_run: true, ..KeeperOptions::default() };
+    let due = run_once(&client, &keeper, &program_id, &dry_run, &reporter).unwrap();
+    assert_eq!(due.iter().map(|entry| entry.address).collect::<Vec<_>>(), vec![loan_address]);
+    assert_eq!(fetch_active_loans(&client, &program_id).unwrap().len(), 1);
+
+    // Quote the liquidation from the state the live pass will see.
+    let pool_accounts = pda::PoolAccounts::new(&program_id, &mint);
+    let staker_address = pda::staker(&program_id, &borrower.pubkey(), &mint);
+    let staker: Staker = fetch_account(&client, &staker_address).unwrap();
+    let pool: RewardPool = fetch_account(&client, &pool_accounts.reward_pool).unwrap();
+    let vault: TokenAccount = fetch_account(&client, &pool_accounts.vault_token).unwrap();
+    let flt_mint: Mint = fetch_account(&client, &pool_accounts.flt_mint).unwrap();
+    let expected =
+        quote::liquidation(&due[0].loan, &staker, &config, vault.amount, &pool, flt_mint.supply).unwrap();
+    assert!(expected.seized > 0);
+    assert_eq!(expected.shortfall, 0);
+
+    // A live pass liquidates it: the loan is closed, the escrow pays the bonus and nothing is
+    // written off.
+    let due = run_once(&client, &keeper, &program_id, &KeeperOptions::default(), &reporter).unwrap();
+    assert_eq!(due.len(), 1);
+    assert!(client.get_account_with_commitment(&loan_address, client.commitment()).unwrap().value.is_none());
+    assert!(fetch_active_loans(&client, &program_id).unwrap().is_empty());
+
+    let keeper_token_account: TokenAccount =
+        fetch_account(&client, &get_associated_token_address(&keeper.pubkey(), &mint)).unwrap();
+    assert_eq!(keeper_token_account.amount, expected.bonus);
+    let staker_after: Staker = fetch_account(&client, &staker_address).unwrap();
+    assert_eq!(staker_after.escrowed_flt, staker.escrowed_flt - expected.seized);
+    assert_eq!(staker_after.borrowed, 0);
+    let pool_after: RewardPool = fetch_account(&client, &pool_accounts.reward_pool).unwrap();
+    assert_eq!(pool_after.active_loan_total, 0);
+    assert_eq!(pool_after.bad_debt, 0);
+
+    // Nothing is left for a further pass.
+    assert!(run_once(&client, &keeper, &program_id, &KeeperOptions::default(), &reporter).unwrap().is_empty());
+}
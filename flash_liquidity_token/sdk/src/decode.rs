@@ -0,0 +1,67 @@
+//
+// Account Decoders
+//
+// Decode raw account data (as returned by getAccountInfo) into the program's account types.
+// The discriminator is checked, so passing the wrong account fails instead of misreading it.
+//
+
+use anchor_lang::{AccountDeserialize, Result};
+use flash_liquidity_token::{BorrowerState, CollateralConfig, Governance, Loan, RewardPool, Staker};
+
+/// Decode any of the program's accounts.
+pub fn account<T: AccountDeserialize>(mut data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut data)
+}
+
+pub fn loan(data: &[u8]) -> Result<Loan> {
+    account(data)
+}
+
+pub fn staker(data: &[u8]) -> Result<Staker> {
+    account(data)
+}
+
+pub fn governance(data: &[u8]) -> Result<Governance> {
+    account(data)
+}
+
+pub fn reward_pool(data: &[u8]) -> Result<RewardPool> {
+    account(data)
+}
+
+pub fn collateral_config(data: &[u8]) -> Result<CollateralConfig> {
+    account(data)
+}
+
+pub fn borrower_state(data: &[u8]) -> Result<BorrowerState> {
+    account(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::AccountSerialize;
+    use flash_liquidity_token::LoanMode;
+
+    #[test]
+    fn round_trips_and_checks_discriminator() {
+        let original = Loan {
+            borrower: Pubkey::new_unique(),
+            collateral_mint: Pubkey::new_unique(),
+            nonce: 4,
+            amount: 1_000,
+            start_slot: 10,
+            due_slot: 110,
+            active: true,
+            mode: LoanMode::Term,
+            bump: 254,
+        };
+        let mut data = Vec::new();
+        original.try_serialize(&mut data).unwrap();
+
+        let decoded = loan(&data).unwrap();
+        assert_eq!((decoded.borrower, decoded.nonce, decoded.due_slot), (original.borrower, 4, 110));
+        assert!(staker(&data).is_err());
+    }
+}
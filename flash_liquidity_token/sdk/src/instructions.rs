@@ -0,0 +1,840 @@
+//
+// Instruction Builders
+//
+// One builder per program instruction. Accounts are filled in from the program's own
+// `accounts::*` structs, so the order always matches the on-chain `#[derive(Accounts)]`.
+//
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use flash_liquidity_token::{
+    accounts, instruction, CollateralConfig, CollateralParams, CollateralRiskParams, CollateralStatus, FeeCurve,
+    GovernanceParams, GovernanceRoles, LoanMode, ParameterChange, SecondaryOracle,
+};
+
+use crate::pda::{self, PoolAccounts};
+
+fn build(program_id: &Pubkey, accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Account passed as `secondary_price` for a collateral, if it has a secondary oracle.
+pub fn secondary_price_account(program_id: &Pubkey, config: &CollateralConfig) -> Option<Pubkey> {
+    match config.secondary_oracle {
+        SecondaryOracle::None => None,
+        SecondaryOracle::Pyth(feed) => Some(feed),
+        SecondaryOracle::Manual => Some(pda::manual_price(program_id, &config.collateral_mint)),
+    }
+}
+
+//
+// Protocol and collateral listings
+//
+
+pub fn initialize(program_id: &Pubkey, admin: &Pubkey, params: GovernanceParams) -> Instruction {
+    build(
+        program_id,
+        accounts::Initialize {
+            admin: *admin,
+            governance: pda::governance(program_id),
+            system_program: system_program::ID,
+        },
+        instruction::Initialize { params },
+    )
+}
+
+pub fn list_collateral(
+    program_id: &Pubkey,
+    listing_manager: &Pubkey,
+    collateral_mint: &Pubkey,
+    params: CollateralParams,
+) -> Instruction {
+    let pool = PoolAccounts::new(program_id, collateral_mint);
+    build(
+        program_id,
+        accounts::ListCollateral {
+            listing_manager: *listing_manager,
+            governance: pda::governance(program_id),
+            collateral_mint: *collateral_mint,
+            collateral_config: pool.collateral_config,
+            vault_account: pool.vault,
+            vault_token_account: pool.vault_token,
+            insurance_token_account: pool.insurance,
+            flt_mint: pool.flt_mint,
+            flt_mint_wrapper: pool.flt_mint_wrapper,
+            reward_pool: pool.reward_pool,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::ListCollateral { params },
+    )
+}
+
+pub fn update_collateral(
+    program_id: &Pubkey,
+    listing_manager: &Pubkey,
+    collateral_mint: &Pubkey,
+    status: CollateralStatus,
+) -> Instruction {
+    build(
+        program_id,
+        accounts::UpdateCollateral {
+            listing_manager: *listing_manager,
+            governance: pda::governance(program_id),
+            collateral_config: pda::collateral_config(program_id, collateral_mint),
+        },
+        instruction::UpdateCollateral { status },
+    )
+}
+
+pub fn set_collateral_fee_curve(
+    program_id: &Pubkey,
+    fee_manager: &Pubkey,
+    collateral_mint: &Pubkey,
+    fee_curve: FeeCurve,
+) -> Instruction {
+    build(
+        program_id,
+        accounts::SetCollateralFeeCurve {
+            fee_manager: *fee_manager,
+            governance: pda::governance(program_id),
+            collateral_config: pda::collateral_config(program_id, collateral_mint),
+        },
+        instruction::SetCollateralFeeCurve { fee_curve },
+    )
+}
+
+pub fn set_collateral_risk(
+    program_id: &Pubkey,
+    risk_manager: &Pubkey,
+    collateral_mint: &Pubkey,
+    params: CollateralRiskParams,
+) -> Instruction {
+    build(
+        program_id,
+        accounts::SetCollateralRisk {
+            risk_manager: *risk_manager,
+            governance: pda::governance(program_id),
+            collateral_config: pda::collateral_config(program_id, collateral_mint),
+        },
+        instruction::SetCollateralRisk { params },
+    )
+}
+
+pub fn delist_collateral(program_id: &Pubkey, listing_manager: &Pubkey, collateral_mint: &Pubkey) -> Instruction {
+    let pool = PoolAccounts::new(program_id, collateral_mint);
+    build(
+        program_id,
+        accounts::DelistCollateral {
+            listing_manager: *listing_manager,
+            governance: pda::governance(program_id),
+            collateral_mint: *collateral_mint,
+            collateral_config: pool.collateral_config,
+            flt_mint: pool.flt_mint,
+            reward_pool: pool.reward_pool,
+        },
+        instruction::DelistCollateral {},
+    )
+}
+
+//
+// Staking
+//
+
+pub fn stake(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    collateral_mint: &Pubkey,
+    user_token_account: &Pubkey,
+    amount: u64,
+    lock_duration: u64,
+) -> Instruction {
+    let pool = PoolAccounts::new(program_id, collateral_mint);
+    build(
+        program_id,
+        accounts::Stake {
+            user: *user,
+            user_token_account: *user_token_account,
+            vault_token_account: pool.vault_token,
+            collateral_mint: *collateral_mint,
+            flt_mint: pool.flt_mint,
+            flt_mint_wrapper: pool.flt_mint_wrapper,
+            stake_escrow: pda::stake_escrow(program_id, user, collateral_mint),
+            governance: pda::governance(program_id),
+            collateral_config: pool.collateral_config,
+            reward_pool: pool.reward_pool,
+            staker: pda::staker(program_id, user, collateral_mint),
+            vault_account: pool.vault,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::Stake { amount, lock_duration },
+    )
+}
+
+pub fn unstake(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    collateral_mint: &Pubkey,
+    user_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let pool = PoolAccounts::new(program_id, collateral_mint);
+    build(
+        program_id,
+        accounts::Unstake {
+            user: *user,
+            user_token_account: *user_token_account,
+            collateral_mint: *collateral_mint,
+            flt_mint: pool.flt_mint,
+            flt_mint_wrapper: pool.flt_mint_wrapper,
+            stake_escrow: pda::stake_escrow(program_id, user, collateral_mint),
+            vault_account: pool.vault,
+            vault_token_account: pool.vault_token,
+            governance: pda::governance(program_id),
+            collateral_config: pool.collateral_config,
+            staker: pda::staker(program_id, user, collateral_mint),
+            reward_pool: pool.reward_pool,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::Unstake { amount },
+    )
+}
+
+pub fn withdraw_stake(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    collateral_mint: &Pubkey,
+    user_flt_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let pool = PoolAccounts::new(program_id, collateral_mint);
+    build(
+        program_id,
+        accounts::WithdrawStake {
+            user: *user,
+            collateral_mint: *collateral_mint,
+            flt_mint: pool.flt_mint,
+            stake_escrow: pda::stake_escrow(program_id, user, collateral_mint),
+            user_flt_token_account: *user_flt_token_account,
+            vault_account: pool.vault,
+            governance: pda::governance(program_id),
+            collateral_config: pool.collateral_config,
+            staker: pda::staker(program_id, user, collateral_mint),
+            reward_pool: pool.reward_pool,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::WithdrawStake { amount },
+    )
+}
+
+pub fn redeem(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    collateral_mint: &Pubkey,
+    user_token_account: &Pubkey,
+    user_flt_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let pool = PoolAccounts::new(program_id, collateral_mint);
+    build(
+        program_id,
+        accounts::Redeem {
+            user: *user,
+            user_token_account: *user_token_account,
+            collateral_mint: *collateral_mint,
+            flt_mint: pool.flt_mint,
+            user_flt_token_account: *user_flt_token_account,
+            vault_account: pool.vault,
+            vault_token_account: pool.vault_token,
+            governance: pda::governance(program_id),
+            collateral_config: pool.collateral_config,
+            reward_pool: pool.reward_pool,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::Redeem { amount },
+    )
+}
+
+pub fn compound_rewards(program_id: &Pubkey, staker_owner: &Pubkey, collateral_mint: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        accounts::CompoundRewards {
+            staker: pda::staker(program_id, staker_owner, collateral_mint),
+            staker_owner: *staker_owner,
+            governance: pda::governance(program_id),
+            reward_pool: pda::reward_pool(program_id, collateral_mint),
+        },
+        instruction::CompoundRewards {},
+    )
+}
+
+pub fn claim_rewards(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    collateral_mint: &Pubkey,
+    user_token_account: &Pubkey,
+) -> Instruction {
+    let pool = PoolAccounts::new(program_id, collateral_mint);
+    build(
+        program_id,
+        accounts::ClaimRewards {
+            user: *user,
+            user_token_account: *user_token_account,
+            vault_account: pool.vault,
+            vault_token_account: pool.vault_token,
+            staker: pda::staker(program_id, user, collateral_mint),
+            governance: pda::governance(program_id),
+            reward_pool: pool.reward_pool,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::ClaimRewards {},
+    )
+}
+
+/// Read-only; simulate it and decode the return data as a little-endian u64.
+pub fn exchange_rate(program_id: &Pubkey, collateral_mint: &Pubkey) -> Instruction {
+    let pool = PoolAccounts::new(program_id, collateral_mint);
+    build(
+        program_id,
+        accounts::ExchangeRate {
+            collateral_mint: *collateral_mint,
+            flt_mint: pool.flt_mint,
+            vault_token_account: pool.vault_token,
+            reward_pool: pool.reward_pool,
+        },
+        instruction::ExchangeRate {},
+    )
+}
+
+//
+// Lending
+//
+
+/// Open a loan against `config`'s collateral.
+/// `loan_nonce` must be the borrower's current `BorrowerState::next_loan_nonce` (0 before their
+/// first loan). A `LoanMode::Flash` borrow must be followed by a `repay` of the same loan in the
+/// same transaction.
+#[allow(clippy::too_many_arguments)]
+pub fn borrow(
+    program_id: &Pubkey,
+    borrower: &Pubkey,
+    borrower_token_account: &Pubkey,
+    config: &CollateralConfig,
+    loan_nonce: u64,
+    amount: u64,
+    loan_duration: u64,
+    mode: LoanMode,
+) -> Instruction {
+    let mint = &config.collateral_mint;
+    let pool = PoolAccounts::new(program_id, mint);
+    build(
+        program_id,
+        accounts::Borrow {
+            borrower: *borrower,
+            borrower_token_account: *borrower_token_account,
+            vault_account: pool.vault,
+            vault_token_account: pool.vault_token,
+            staker: pda::staker(program_id, borrower, mint),
+            borrower_state: pda::borrower_state(program_id, borrower),
+            loan: pda::loan(program_id, borrower, mint, loan_nonce),
+            governance: pda::governance(program_id),
+            collateral_config: pool.collateral_config,
+            reward_pool: pool.reward_pool,
+            flt_mint: pool.flt_mint,
+            pyth_price: config.pyth_feed,
+            secondary_price: secondary_price_account(program_id, config),
+            instructions_sysvar: sysvar::instructions::ID,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            insurance_token_account: pool.insurance,
+        },
+        instruction::Borrow { amount, loan_duration, mode },
+    )
+}
+
+/// Single-instruction flash loan. `callback_accounts` are passed through to `callback_program`
+/// together with `callback_data`.
+#[allow(clippy::too_many_arguments)]
+pub fn flash_loan(
+    program_id: &Pubkey,
+    borrower: &Pubkey,
+    borrower_token_account: &Pubkey,
+    config: &CollateralConfig,
+    callback_program: &Pubkey,
+    callback_accounts: &[AccountMeta],
+    amount: u64,
+    callback_data: Vec<u8>,
+) -> Instruction {
+    let mint = &config.collateral_mint;
+    let pool = PoolAccounts::new(program_id, mint);
+    let mut ix = build(
+        program_id,
+        accounts::FlashLoan {
+            borrower: *borrower,
+            borrower_token_account: *borrower_token_account,
+            collateral_mint: *mint,
+            vault_account: pool.vault,
+            vault_token_account: pool.vault_token,
+            governance: pda::governance(program_id),
+            collateral_config: pool.collateral_config,
+            reward_pool: pool.reward_pool,
+            callback_program: *callback_program,
+            pyth_price: config.pyth_feed,
+            secondary_price: secondary_price_account(program_id, config),
+            token_program: anchor_spl::token::ID,
+            insurance_token_account: pool.insurance,
+        },
+        instruction::FlashLoan { amount, callback_data },
+    );
+    ix.accounts.extend_from_slice(callback_accounts);
+    ix
+}
+
+/// Repay `amount` (principal plus any late penalty) of the borrower's loan `loan_nonce`.
+pub fn repay(
+    program_id: &Pubkey,
+    borrower: &Pubkey,
+    borrower_token_account: &Pubkey,
+    collateral_mint: &Pubkey,
+    loan_nonce: u64,
+    amount: u64,
+) -> Instruction {
+    let pool = PoolAccounts::new(program_id, collateral_mint);
+    build(
+        program_id,
+        accounts::Repay {
+            borrower: *borrower,
+            borrower_token_account: *borrower_token_account,
+            vault_account: pool.vault,
+            vault_token_account: pool.vault_token,
+            loan: pda::loan(program_id, borrower, collateral_mint, loan_nonce),
+            staker: pda::staker(program_id, borrower, collateral_mint),
+            governance: pda::governance(program_id),
+            collateral_config: pool.collateral_config,
+            reward_pool: pool.reward_pool,
+            borrower_state: pda::borrower_state(program_id, borrower),
+            token_program: anchor_spl::token::ID,
+            insurance_token_account: pool.insurance,
+        },
+        instruction::Repay { amount },
+    )
+}
+
+pub fn extend_loan(
+    program_id: &Pubkey,
+    borrower: &Pubkey,
+    borrower_token_account: &Pubkey,
+    config: &CollateralConfig,
+    loan_nonce: u64,
+    extension_slots: u64,
+) -> Instruction {
+    let mint = &config.collateral_mint;
+    build(
+        program_id,
+        accounts::ExtendLoan {
+            borrower: *borrower,
+            borrower_token_account: *borrower_token_account,
+            vault_token_account: pda::vault_token(program_id, mint),
+            loan: pda::loan(program_id, borrower, mint, loan_nonce),
+            governance: pda::governance(program_id),
+            collateral_config: pda::collateral_config(program_id, mint),
+            reward_pool: pda::reward_pool(program_id, mint),
+            pyth_price: config.pyth_feed,
+            secondary_price: secondary_price_account(program_id, config),
+            token_program: anchor_spl::token::ID,
+            vault_account: pda::vault(program_id, mint),
+            insurance_token_account: pda::insurance(program_id, mint),
+        },
+        instruction::ExtendLoan { extension_slots },
+    )
+}
+
+/// Liquidate `borrower`'s overdue loan `loan_nonce`. The bonus is paid to
+/// `liquidator_token_account`, which must hold the collateral mint.
+pub fn liquidate(
+    program_id: &Pubkey,
+    liquidator: &Pubkey,
+    liquidator_token_account: &Pubkey,
+    borrower: &Pubkey,
+    config: &CollateralConfig,
+    loan_nonce: u64,
+) -> Instruction {
+    let mint = &config.collateral_mint;
+    let pool = PoolAccounts::new(program_id, mint);
+    build(
+        program_id,
+        accounts::Liquidate {
+            liquidator: *liquidator,
+            liquidator_token_account: *liquidator_token_account,
+            vault_account: pool.vault,
+            vault_token_account: pool.vault_token,
+            loan: pda::loan(program_id, borrower, mint, loan_nonce),
+            staker: pda::staker(program_id, borrower, mint),
+            governance: pda::governance(program_id),
+            collateral_config: pool.collateral_config,
+            reward_pool: pool.reward_pool,
+            borrower_state: pda::borrower_state(program_id, borrower),
+            pyth_price: config.pyth_feed,
+            secondary_price: secondary_price_account(program_id, config),
+            token_program: anchor_spl::token::ID,
+            flt_mint: pool.flt_mint,
+            stake_escrow: pda::stake_escrow(program_id, borrower, mint),
+            insurance_token_account: pool.insurance,
+            borrower: *borrower,
+        },
+        instruction::Liquidate {},
+    )
+}
+
+//
+// Governance
+//
+
+pub fn update_governance_parameters(program_id: &Pubkey, admin: &Pubkey, params: GovernanceParams) -> Instruction {
+    build(
+        program_id,
+        accounts::UpdateGovernanceParameters {
+            admin: *admin,
+            governance: pda::governance(program_id),
+        },
+        instruction::UpdateGovernanceParameters { params },
+    )
+}
+
+pub fn set_fee_parameters(
+    program_id: &Pubkey,
+    fee_manager: &Pubkey,
+    reward_share_bps: u64,
+    insurance_share_bps: u64,
+) -> Instruction {
+    build(
+        program_id,
+        accounts::SetFeeParameters {
+            fee_manager: *fee_manager,
+            governance: pda::governance(program_id),
+        },
+        instruction::SetFeeParameters { reward_share_bps, insurance_share_bps },
+    )
+}
+
+pub fn set_liquidation_grace_slots(
+    program_id: &Pubkey,
+    risk_manager: &Pubkey,
+    liquidation_grace_slots: u64,
+) -> Instruction {
+    build(
+        program_id,
+        accounts::SetLiquidationGraceSlots {
+            risk_manager: *risk_manager,
+            governance: pda::governance(program_id),
+        },
+        instruction::SetLiquidationGraceSlots { liquidation_grace_slots },
+    )
+}
+
+pub fn set_manual_price(program_id: &Pubkey, risk_manager: &Pubkey, collateral_mint: &Pubkey, price: u64) -> Instruction {
+    build(
+        program_id,
+        accounts::SetManualPrice {
+            risk_manager: *risk_manager,
+            governance: pda::governance(program_id),
+            collateral_config: pda::collateral_config(program_id, collateral_mint),
+            manual_price: pda::manual_price(program_id, collateral_mint),
+            system_program: system_program::ID,
+        },
+        instruction::SetManualPrice { price },
+    )
+}
+
+pub fn set_roles(program_id: &Pubkey, admin: &Pubkey, roles: GovernanceRoles) -> Instruction {
+    build(
+        program_id,
+        accounts::SetRoles {
+            admin: *admin,
+            governance: pda::governance(program_id),
+        },
+        instruction::SetRoles { roles },
+    )
+}
+
+/// `flags` is a bitmap of the program's `PAUSE_*` constants.
+pub fn pause(program_id: &Pubkey, authority: &Pubkey, flags: u8) -> Instruction {
+    build(
+        program_id,
+        accounts::SetPause {
+            authority: *authority,
+            governance: pda::governance(program_id),
+        },
+        instruction::Pause { flags },
+    )
+}
+
+pub fn unpause(program_id: &Pubkey, authority: &Pubkey, flags: u8) -> Instruction {
+    build(
+        program_id,
+        accounts::SetPause {
+            authority: *authority,
+            governance: pda::governance(program_id),
+        },
+        instruction::Unpause { flags },
+    )
+}
+
+pub fn propose_admin(program_id: &Pubkey, admin: &Pubkey, new_admin: Pubkey) -> Instruction {
+    build(
+        program_id,
+        accounts::ProposeAdmin {
+            admin: *admin,
+            governance: pda::governance(program_id),
+        },
+        instruction::ProposeAdmin { new_admin },
+    )
+}
+
+pub fn accept_admin(program_id: &Pubkey, pending_admin: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        accounts::AcceptAdmin {
+            pending_admin: *pending_admin,
+            governance: pda::governance(program_id),
+        },
+        instruction::AcceptAdmin {},
+    )
+}
+
+/// `change_id` must be the current `Governance::next_change_id`. `authority` is the admin or the
+/// role owning `change` (`ParameterChange::authority`).
+pub fn queue_parameter_change(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    change_id: u64,
+    change: ParameterChange,
+) -> Instruction {
+    build(
+        program_id,
+        accounts::QueueParameterChange {
+            authority: *authority,
+            governance: pda::governance(program_id),
+            pending_change: pda::pending_change(program_id, change_id),
+            system_program: system_program::ID,
+        },
+        instruction::QueueParameterChange { change },
+    )
+}
+
+/// `collateral_mint` is required when the queued change targets a collateral
+/// (`ParameterChange::collateral_mint`).
+pub fn execute_parameter_change(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    change_id: u64,
+    collateral_mint: Option<&Pubkey>,
+) -> Instruction {
+    build(
+        program_id,
+        accounts::ExecuteParameterChange {
+            authority: *authority,
+            governance: pda::governance(program_id),
+            pending_change: pda::pending_change(program_id, change_id),
+            collateral_config: collateral_mint.map(|mint| pda::collateral_config(program_id, mint)),
+        },
+        instruction::ExecuteParameterChange {},
+    )
+}
+
+pub fn cancel_parameter_change(program_id: &Pubkey, authority: &Pubkey, change_id: u64) -> Instruction {
+    build(
+        program_id,
+        accounts::CancelParameterChange {
+            authority: *authority,
+            governance: pda::governance(program_id),
+            pending_change: pda::pending_change(program_id, change_id),
+        },
+        instruction::CancelParameterChange {},
+    )
+}
+
+//
+// Voting
+//
+
+/// `vote_collateral_mint` is `Governance::vote_collateral_mint`; votes are cast with its FLT.
+pub fn deposit_votes(
+    program_id: &Pubkey,
+    voter: &Pubkey,
+    vote_collateral_mint: &Pubkey,
+    voter_flt_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        accounts::DepositVotes {
+            voter: *voter,
+            governance: pda::governance(program_id),
+            flt_mint: pda::flt_mint(program_id, vote_collateral_mint),
+            voter_flt_account: *voter_flt_account,
+            voter_record: pda::voter_record(program_id, voter),
+            vote_escrow: pda::vote_escrow(program_id, voter),
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::DepositVotes { amount },
+    )
+}
+
+pub fn withdraw_votes(
+    program_id: &Pubkey,
+    voter: &Pubkey,
+    vote_collateral_mint: &Pubkey,
+    voter_flt_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        accounts::WithdrawVotes {
+            voter: *voter,
+            governance: pda::governance(program_id),
+            flt_mint: pda::flt_mint(program_id, vote_collateral_mint),
+            voter_flt_account: *voter_flt_account,
+            voter_record: pda::voter_record(program_id, voter),
+            vote_escrow: pda::vote_escrow(program_id, voter),
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::WithdrawVotes { amount },
+    )
+}
+
+/// `proposal_id` must be the current `Governance::next_proposal_id`.
+pub fn create_proposal(
+    program_id: &Pubkey,
+    proposer: &Pubkey,
+    vote_collateral_mint: &Pubkey,
+    proposal_id: u64,
+    change: ParameterChange,
+) -> Instruction {
+    build(
+        program_id,
+        accounts::CreateProposal {
+            proposer: *proposer,
+            governance: pda::governance(program_id),
+            flt_mint: pda::flt_mint(program_id, vote_collateral_mint),
+            voter_record: pda::voter_record(program_id, proposer),
+            proposal: pda::proposal(program_id, proposal_id),
+            system_program: system_program::ID,
+        },
+        instruction::CreateProposal { change },
+    )
+}
+
+pub fn cast_vote(program_id: &Pubkey, voter: &Pubkey, proposal_id: u64, approve: bool) -> Instruction {
+    let proposal = pda::proposal(program_id, proposal_id);
+    build(
+        program_id,
+        accounts::CastVote {
+            voter: *voter,
+            proposal,
+            voter_record: pda::voter_record(program_id, voter),
+            vote_record: pda::vote_record(program_id, &proposal, voter),
+            system_program: system_program::ID,
+        },
+        instruction::CastVote { approve },
+    )
+}
+
+/// Permissionless. `collateral_mint` is required when the proposal is a `ParameterChange::Collateral`.
+pub fn execute_proposal(program_id: &Pubkey, proposal_id: u64, collateral_mint: Option<&Pubkey>) -> Instruction {
+    build(
+        program_id,
+        accounts::ExecuteProposal {
+            governance: pda::governance(program_id),
+            proposal: pda::proposal(program_id, proposal_id),
+            collateral_config: collateral_mint.map(|mint| pda::collateral_config(program_id, mint)),
+        },
+        instruction::ExecuteProposal {},
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    fn config(secondary_oracle: SecondaryOracle) -> CollateralConfig {
+        CollateralConfig {
+            collateral_mint: Pubkey::new_unique(),
+            status: CollateralStatus::Active,
+            max_borrow_ratio: 5_000,
+            liquidation_penalty_bps: 10,
+            borrow_cap: u64::MAX,
+            pyth_feed: Pubkey::new_unique(),
+            max_price_age_secs: 60,
+            secondary_oracle,
+            max_deviation_bps: 500,
+            fee_curve: FeeCurve {
+                base_fee_bps: 5,
+                slope1_bps: 20,
+                slope2_bps: 200,
+                optimal_utilization_bps: 8_000,
+                reference_price: 0,
+            },
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn repay_matches_flash_borrow_scan_indices() {
+        // `borrow` in flash mode looks for the loan at index 4 and the vault token account at 3.
+        let program_id = flash_liquidity_token::ID;
+        let (borrower, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = repay(&program_id, &borrower, &Pubkey::new_unique(), &mint, 3, 500);
+        assert_eq!(ix.accounts[3].pubkey, pda::vault_token(&program_id, &mint));
+        assert_eq!(ix.accounts[4].pubkey, pda::loan(&program_id, &borrower, &mint, 3));
+        assert_eq!(ix.data[..8], instruction::Repay::DISCRIMINATOR);
+        assert_eq!(ix.data[8..16], 500u64.to_le_bytes());
+    }
+
+    #[test]
+    fn oracle_accounts_follow_collateral_config() {
+        let program_id = flash_liquidity_token::ID;
+        let liquidator = Pubkey::new_unique();
+        let borrower = Pubkey::new_unique();
+
+        let none = config(SecondaryOracle::None);
+        let ix = liquidate(&program_id, &liquidator, &Pubkey::new_unique(), &borrower, &none, 0);
+        assert_eq!(ix.accounts[10].pubkey, none.pyth_feed);
+        // An absent optional account is passed as the program id.
+        assert_eq!(ix.accounts[11].pubkey, program_id);
+
+        let manual = config(SecondaryOracle::Manual);
+        let ix = liquidate(&program_id, &liquidator, &Pubkey::new_unique(), &borrower, &manual, 0);
+        assert_eq!(ix.accounts[11].pubkey, pda::manual_price(&program_id, &manual.collateral_mint));
+    }
+
+    #[test]
+    fn flash_loan_appends_callback_accounts() {
+        let program_id = flash_liquidity_token::ID;
+        let callback_accounts = [AccountMeta::new(Pubkey::new_unique(), false)];
+        let ix = flash_loan(
+            &program_id,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &config(SecondaryOracle::None),
+            &Pubkey::new_unique(),
+            &callback_accounts,
+            1_000,
+            vec![1, 2, 3],
+        );
+        assert_eq!(ix.accounts.last(), callback_accounts.last());
+    }
+}
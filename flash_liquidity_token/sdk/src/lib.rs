@@ -0,0 +1,15 @@
+//! Rust client SDK for the flash liquidity token program.
+//!
+//! - [`pda`]: addresses of the program's accounts.
+//! - [`instructions`]: a builder for every instruction, with accounts in program order.
+//! - [`decode`]: decoders for account data fetched over RPC.
+//! - [`quote`]: fee, repayment, liquidation and exchange-rate quotes using the program's math.
+//!
+//! The program's account and parameter types are re-exported from `flash_liquidity_token`.
+
+pub use flash_liquidity_token::{self as program, ID};
+
+pub mod decode;
+pub mod instructions;
+pub mod pda;
+pub mod quote;
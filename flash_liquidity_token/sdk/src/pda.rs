@@ -0,0 +1,152 @@
+//
+// PDAs
+//
+// Program derived addresses, with the same seeds as the program's account constraints.
+//
+
+use anchor_lang::prelude::Pubkey;
+
+fn find(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(seeds, program_id).0
+}
+
+/// seeds = [b"governance"].
+pub fn governance(program_id: &Pubkey) -> Pubkey {
+    find(&[b"governance"], program_id)
+}
+
+/// seeds = [b"collateral_config", collateral_mint].
+pub fn collateral_config(program_id: &Pubkey, collateral_mint: &Pubkey) -> Pubkey {
+    find(&[b"collateral_config", collateral_mint.as_ref()], program_id)
+}
+
+/// seeds = [b"vault", collateral_mint]. Authority of the vault token account.
+pub fn vault(program_id: &Pubkey, collateral_mint: &Pubkey) -> Pubkey {
+    find(&[b"vault", collateral_mint.as_ref()], program_id)
+}
+
+/// seeds = [b"vault_token", collateral_mint].
+pub fn vault_token(program_id: &Pubkey, collateral_mint: &Pubkey) -> Pubkey {
+    find(&[b"vault_token", collateral_mint.as_ref()], program_id)
+}
+
+/// seeds = [b"insurance", collateral_mint]. The insurance fund's token account; owned by the vault PDA.
+pub fn insurance(program_id: &Pubkey, collateral_mint: &Pubkey) -> Pubkey {
+    find(&[b"insurance", collateral_mint.as_ref()], program_id)
+}
+
+/// seeds = [b"flt_mint", collateral_mint].
+pub fn flt_mint(program_id: &Pubkey, collateral_mint: &Pubkey) -> Pubkey {
+    find(&[b"flt_mint", collateral_mint.as_ref()], program_id)
+}
+
+/// seeds = [b"flt_mint_wrapper", collateral_mint]. Mint authority of the FLT mint.
+pub fn flt_mint_wrapper(program_id: &Pubkey, collateral_mint: &Pubkey) -> Pubkey {
+    find(&[b"flt_mint_wrapper", collateral_mint.as_ref()], program_id)
+}
+
+/// seeds = [b"reward_pool", collateral_mint].
+pub fn reward_pool(program_id: &Pubkey, collateral_mint: &Pubkey) -> Pubkey {
+    find(&[b"reward_pool", collateral_mint.as_ref()], program_id)
+}
+
+/// seeds = [b"staker", owner, collateral_mint].
+pub fn staker(program_id: &Pubkey, owner: &Pubkey, collateral_mint: &Pubkey) -> Pubkey {
+    find(&[b"staker", owner.as_ref(), collateral_mint.as_ref()], program_id)
+}
+
+/// seeds = [b"stake_escrow", owner, collateral_mint]. Holds the staker's FLT; owned by the vault PDA.
+pub fn stake_escrow(program_id: &Pubkey, owner: &Pubkey, collateral_mint: &Pubkey) -> Pubkey {
+    find(&[b"stake_escrow", owner.as_ref(), collateral_mint.as_ref()], program_id)
+}
+
+/// seeds = [b"borrower", borrower]. Holds the borrower's next loan nonce.
+pub fn borrower_state(program_id: &Pubkey, borrower: &Pubkey) -> Pubkey {
+    find(&[b"borrower", borrower.as_ref()], program_id)
+}
+
+/// seeds = [b"loan", borrower, collateral_mint, nonce.to_le_bytes()].
+pub fn loan(program_id: &Pubkey, borrower: &Pubkey, collateral_mint: &Pubkey, nonce: u64) -> Pubkey {
+    find(&[b"loan", borrower.as_ref(), collateral_mint.as_ref(), &nonce.to_le_bytes()], program_id)
+}
+
+/// seeds = [b"manual_price", collateral_mint].
+pub fn manual_price(program_id: &Pubkey, collateral_mint: &Pubkey) -> Pubkey {
+    find(&[b"manual_price", collateral_mint.as_ref()], program_id)
+}
+
+/// seeds = [b"pending_change", id.to_le_bytes()].
+pub fn pending_change(program_id: &Pubkey, id: u64) -> Pubkey {
+    find(&[b"pending_change", &id.to_le_bytes()], program_id)
+}
+
+/// seeds = [b"proposal", id.to_le_bytes()].
+pub fn proposal(program_id: &Pubkey, id: u64) -> Pubkey {
+    find(&[b"proposal", &id.to_le_bytes()], program_id)
+}
+
+/// seeds = [b"voter", voter].
+pub fn voter_record(program_id: &Pubkey, voter: &Pubkey) -> Pubkey {
+    find(&[b"voter", voter.as_ref()], program_id)
+}
+
+/// seeds = [b"vote_escrow", voter]. Holds the voter's deposited FLT.
+pub fn vote_escrow(program_id: &Pubkey, voter: &Pubkey) -> Pubkey {
+    find(&[b"vote_escrow", voter.as_ref()], program_id)
+}
+
+/// seeds = [b"vote", proposal, voter].
+pub fn vote_record(program_id: &Pubkey, proposal: &Pubkey, voter: &Pubkey) -> Pubkey {
+    find(&[b"vote", proposal.as_ref(), voter.as_ref()], program_id)
+}
+
+/// Every per-collateral account of a pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolAccounts {
+    pub collateral_mint: Pubkey,
+    pub collateral_config: Pubkey,
+    pub vault: Pubkey,
+    pub vault_token: Pubkey,
+    pub insurance: Pubkey,
+    pub flt_mint: Pubkey,
+    pub flt_mint_wrapper: Pubkey,
+    pub reward_pool: Pubkey,
+}
+
+impl PoolAccounts {
+    pub fn new(program_id: &Pubkey, collateral_mint: &Pubkey) -> Self {
+        Self {
+            collateral_mint: *collateral_mint,
+            collateral_config: collateral_config(program_id, collateral_mint),
+            vault: vault(program_id, collateral_mint),
+            vault_token: vault_token(program_id, collateral_mint),
+            insurance: insurance(program_id, collateral_mint),
+            flt_mint: flt_mint(program_id, collateral_mint),
+            flt_mint_wrapper: flt_mint_wrapper(program_id, collateral_mint),
+            reward_pool: reward_pool(program_id, collateral_mint),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loans_are_distinct_per_nonce() {
+        let program_id = flash_liquidity_token::ID;
+        let (borrower, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_ne!(loan(&program_id, &borrower, &mint, 0), loan(&program_id, &borrower, &mint, 1));
+        assert_eq!(loan(&program_id, &borrower, &mint, 7), loan(&program_id, &borrower, &mint, 7));
+    }
+
+    #[test]
+    fn pool_accounts_match_single_derivations() {
+        let program_id = flash_liquidity_token::ID;
+        let mint = Pubkey::new_unique();
+        let pool = PoolAccounts::new(&program_id, &mint);
+        assert_eq!(pool.vault_token, vault_token(&program_id, &mint));
+        assert_eq!(pool.reward_pool, reward_pool(&program_id, &mint));
+        assert_ne!(pool.vault, pool.vault_token);
+    }
+}
@@ -0,0 +1,343 @@
+//
+// Quotes
+//
+// Off-chain quotes built from the program's own `math` and `oracle` functions, in the same
+// order as the instructions apply them, so a quote matches what the program will charge
+// given the same account state, slot and oracle price.
+//
+
+use anchor_lang::Result;
+use flash_liquidity_token::{math, oracle, CollateralConfig, Loan, RewardPool, Staker, EXCHANGE_RATE_SCALE};
+
+/// Fee charged on a loan at a given pool utilization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeQuote {
+    pub utilization_bps: u64,
+    pub fee_bps: u64,
+    pub fee: u64,
+}
+
+/// Split of a `repay` payment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RepayQuote {
+    pub principal: u64,
+    pub penalty: u64,
+    pub remaining_principal: u64,
+}
+
+/// Outcome of liquidating a loan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiquidationQuote {
+    pub penalty: u64,                      // penalty owed on the principal
+    pub seized: u64,                       // escrowed FLT burned from the borrower's stake escrow
+    pub bonus: u64,                        // collateral paid to the liquidator out of the seized FLT's value
+    pub shortfall: u64,                    // principal left uncovered (recorded as bad debt)
+    pub from_insurance: u64,
+    pub socialized: u64,
+}
+
+fn fee_at(config: &CollateralConfig, utilization_bps: u64, amount: u64, price: u64) -> Result<FeeQuote> {
+    let fee_bps =
+        oracle::adjust_fee_bps(config.fee_curve.fee_bps(utilization_bps)?, price, config.fee_curve.reference_price)?;
+    Ok(FeeQuote {
+        utilization_bps,
+        fee_bps,
+        fee: math::fee_amount(amount, fee_bps)?,
+    })
+}
+
+/// Utilization of the vault's assets after lending out a further `amount`, given the vault token
+/// account balance.
+pub fn utilization_bps(vault_balance: u64, pool: &RewardPool, amount: u64) -> Result<u64> {
+    math::utilization_bps(math::add(pool.active_loan_total, amount)?, vault_assets(vault_balance, pool)?)
+}
+
+/// Fee for borrowing `amount` with `borrow` or `flash_loan` at the normalized oracle `price`
+/// (see `oracle::PRICE_SCALE`), with the vault token account holding `vault_balance`. `borrow`
+/// pays out `amount - fee`; `flash_loan` pays out `amount` and must get `amount + fee` back.
+pub fn borrow_fee(
+    config: &CollateralConfig,
+    vault_balance: u64,
+    pool: &RewardPool,
+    amount: u64,
+    price: u64,
+) -> Result<FeeQuote> {
+    fee_at(config, utilization_bps(vault_balance, pool, amount)?, amount, price)
+}
+
+/// Fee for `extend_loan` on `loan`: the borrow fee at the vault's current utilization, on the
+/// outstanding principal.
+pub fn extend_fee(
+    config: &CollateralConfig,
+    vault_balance: u64,
+    pool: &RewardPool,
+    loan: &Loan,
+    price: u64,
+) -> Result<FeeQuote> {
+    fee_at(config, utilization_bps(vault_balance, pool, 0)?, loan.amount, price)
+}
+
+/// How `repay` splits a payment of `amount` on `loan` at `current_slot`.
+pub fn repayment(loan: &Loan, config: &CollateralConfig, amount: u64, current_slot: u64) -> Result<RepayQuote> {
+    let overdue_slots = current_slot.saturating_sub(loan.due_slot);
+    let (principal, penalty) =
+        math::split_repayment(amount, loan.amount, overdue_slots, config.liquidation_penalty_bps)?;
+    Ok(RepayQuote {
+        principal,
+        penalty,
+        remaining_principal: math::sub(loan.amount, principal)?,
+    })
+}
+
+/// Amount that fully repays `loan` at `current_slot`: principal plus any late penalty.
+pub fn payoff_amount(loan: &Loan, config: &CollateralConfig, current_slot: u64) -> Result<u64> {
+    let overdue_slots = current_slot.saturating_sub(loan.due_slot);
+    math::add(loan.amount, math::late_penalty(loan.amount, overdue_slots, config.liquidation_penalty_bps)?)
+}
+
+/// What `liquidate` does to `loan`, secured by the FLT escrowed in the borrower's `staker` record,
+/// with the vault token account holding `vault_balance` and `flt_supply` FLT outstanding.
+pub fn liquidation(
+    loan: &Loan,
+    staker: &Staker,
+    config: &CollateralConfig,
+    vault_balance: u64,
+    pool: &RewardPool,
+    flt_supply: u64,
+) -> Result<LiquidationQuote> {
+    let penalty = math::liquidation_bonus(loan.amount, config.liquidation_penalty_bps)?;
+    let (seized, bonus, shortfall) = math::liquidate_escrow(
+        loan.amount,
+        penalty,
+        staker.escrowed_flt,
+        vault_assets(vault_balance, pool)?,
+        flt_supply,
+    )?;
+    let (from_insurance, socialized) = math::loss_waterfall(shortfall, pool.insurance_fund)?;
+    Ok(LiquidationQuote { penalty, seized, bonus, shortfall, from_insurance, socialized })
+}
+
+/// Largest further term loan `staker` can open against `config`: the escrowed FLT's collateral
+/// value at the current exchange rate, at the max borrow ratio, less term principal outstanding.
+pub fn max_borrow(
+    staker: &Staker,
+    config: &CollateralConfig,
+    vault_balance: u64,
+    pool: &RewardPool,
+    flt_supply: u64,
+) -> Result<u64> {
+    let escrow_value = unstake_assets(staker.escrowed_flt, vault_balance, pool, flt_supply)?;
+    Ok(math::max_borrow(escrow_value, config.max_borrow_ratio)?.saturating_sub(staker.borrowed))
+}
+
+/// Collateral backing the pool's FLT, given the vault token account balance. The insurance fund
+/// is held in its own token account and is not part of it.
+pub fn vault_assets(vault_balance: u64, pool: &RewardPool) -> Result<u64> {
+    math::vault_assets(vault_balance, pool.active_loan_total, pool.reserved_rewards)
+}
+
+/// FLT minted by `stake` for depositing `amount` collateral.
+pub fn stake_shares(amount: u64, vault_balance: u64, pool: &RewardPool, flt_supply: u64) -> Result<u64> {
+    math::shares_for_deposit(amount, vault_assets(vault_balance, pool)?, flt_supply)
+}
+
+/// Collateral paid by `unstake` for burning `flt_amount` FLT.
+pub fn unstake_assets(flt_amount: u64, vault_balance: u64, pool: &RewardPool, flt_supply: u64) -> Result<u64> {
+    math::assets_for_shares(flt_amount, vault_assets(vault_balance, pool)?, flt_supply)
+}
+
+/// Collateral per FLT, scaled by `EXCHANGE_RATE_SCALE`. Same value as the `exchange_rate` instruction.
+pub fn exchange_rate(vault_balance: u64, pool: &RewardPool, flt_supply: u64) -> Result<u64> {
+    unstake_assets(EXCHANGE_RATE_SCALE, vault_balance, pool, flt_supply)
+}
+
+/// Fees `claim_rewards` would pay `staker` now.
+pub fn claimable_rewards(staker: &Staker, pool: &RewardPool) -> Result<u64> {
+    let mut settled = staker.clone();
+    settled.settle_rewards(pool.acc_fee_per_share)?;
+    Ok(settled.pending_rewards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use flash_liquidity_token::{CollateralStatus, FeeCurve, LoanMode, SecondaryOracle};
+
+    const REFERENCE_PRICE: u64 = 100 * oracle::PRICE_SCALE;
+
+    fn config() -> CollateralConfig {
+        CollateralConfig {
+            collateral_mint: Pubkey::new_unique(),
+            status: CollateralStatus::Active,
+            max_borrow_ratio: 5_000,
+            liquidation_penalty_bps: 10,
+            borrow_cap: u64::MAX,
+            pyth_feed: Pubkey::new_unique(),
+            max_price_age_secs: 60,
+            secondary_oracle: SecondaryOracle::None,
+            max_deviation_bps: 500,
+            fee_curve: FeeCurve {
+                base_fee_bps: 5,
+                slope1_bps: 20,
+                slope2_bps: 200,
+                optimal_utilization_bps: 8_000,
+                reference_price: REFERENCE_PRICE,
+            },
+            bump: 255,
+        }
+    }
+
+    fn pool(total_staked: u64, active_loan_total: u64, insurance_fund: u64) -> RewardPool {
+        RewardPool {
+            collateral_mint: Pubkey::new_unique(),
+            total_staked,
+            accrued_fees: 0,
+            active_loan_total,
+            update_counter: 0,
+            acc_fee_per_share: 0,
+            reserved_rewards: 0,
+            insurance_fund,
+            bad_debt: 0,
+            flash_loan_active: false,
+            bump: 255,
+        }
+    }
+
+    fn loan(amount: u64, due_slot: u64) -> Loan {
+        Loan {
+            borrower: Pubkey::new_unique(),
+            collateral_mint: Pubkey::new_unique(),
+            nonce: 0,
+            amount,
+            start_slot: 0,
+            due_slot,
+            active: true,
+            mode: LoanMode::Term,
+            bump: 255,
+        }
+    }
+
+    fn staker(staked_amount: u64) -> Staker {
+        Staker {
+            staked_amount,
+            collateral_mint: Pubkey::new_unique(),
+            last_compound_slot: 0,
+            lock_end_slot: 0,
+            reward_debt: 0,
+            pending_rewards: 0,
+            escrowed_flt: staked_amount,
+            borrowed: 0,
+        }
+    }
+
+    #[test]
+    fn borrow_fee_follows_curve_and_oracle() {
+        // 40% utilization: 5 + 20 * 4,000 / 8,000 = 15 bps, unchanged at the reference price.
+        let quote = borrow_fee(&config(), 1_000_000, &pool(1_000_000, 0, 0), 400_000, REFERENCE_PRICE).unwrap();
+        assert_eq!(quote, FeeQuote { utilization_bps: 4_000, fee_bps: 15, fee: 600 });
+
+        // Half the reference price doubles the fee.
+        let quote = borrow_fee(&config(), 1_000_000, &pool(1_000_000, 0, 0), 400_000, REFERENCE_PRICE / 2).unwrap();
+        assert_eq!((quote.fee_bps, quote.fee), (30, 1_200));
+    }
+
+    #[test]
+    fn borrow_fee_ignores_reward_weight() {
+        // The same vault with its weight boosted and compounded, or with all FLT withdrawn
+        // from escrow, charges the same fee.
+        let quote = borrow_fee(&config(), 1_000_000, &pool(1_000_000, 0, 0), 400_000, REFERENCE_PRICE).unwrap();
+        for total_staked in [1_500_000, 1_000_000_000, 0] {
+            let pool = pool(total_staked, 0, 0);
+            assert_eq!(borrow_fee(&config(), 1_000_000, &pool, 400_000, REFERENCE_PRICE).unwrap(), quote);
+        }
+    }
+
+    #[test]
+    fn extend_fee_uses_current_utilization() {
+        // 600,000 on hand + 400,000 lent: 40% of the vault's assets.
+        let quote =
+            extend_fee(&config(), 600_000, &pool(1_000_000, 400_000, 0), &loan(400_000, 100), REFERENCE_PRICE).unwrap();
+        assert_eq!(quote.utilization_bps, 4_000);
+        assert_eq!(quote.fee, 600);
+    }
+
+    #[test]
+    fn payoff_amount_clears_the_loan() {
+        let loan = loan(10_000, 100);
+        let payoff = payoff_amount(&loan, &config(), 105).unwrap();
+        // 10 bps per slot for 5 slots on 10,000.
+        assert_eq!(payoff, 10_050);
+        let quote = repayment(&loan, &config(), payoff, 105).unwrap();
+        assert_eq!(quote, RepayQuote { principal: 10_000, penalty: 50, remaining_principal: 0 });
+        assert!(repayment(&loan, &config(), payoff - 1, 105).unwrap().remaining_principal > 0);
+    }
+
+    #[test]
+    fn liquidation_runs_the_loss_waterfall() {
+        // 700 on hand + 2,300 lent = 3,000 backing 1,000 FLT: 2 collateral per FLT with the
+        // virtual offsets. 400 escrowed FLT is worth 800, so against 2,000 principal it is all
+        // seized, 2 of it pays the bonus and 1,202 is short; 300 of that is insured.
+        let mut staker = staker(400);
+        staker.staked_amount = 600; // boosted weight does not count
+        let quote = liquidation(&loan(2_000, 0), &staker, &config(), 700, &pool(5_000, 2_300, 300), 1_000).unwrap();
+        assert_eq!(
+            quote,
+            LiquidationQuote { penalty: 2, seized: 400, bonus: 2, shortfall: 1_202, from_insurance: 300, socialized: 902 }
+        );
+
+        // A well-covered loan only seizes FLT worth principal plus penalty.
+        staker.escrowed_flt = 2_000;
+        let quote = liquidation(&loan(2_000, 0), &staker, &config(), 700, &pool(5_000, 2_300, 300), 1_000).unwrap();
+        assert_eq!(
+            quote,
+            LiquidationQuote { penalty: 2, seized: 1_001, bonus: 2, shortfall: 0, from_insurance: 0, socialized: 0 }
+        );
+    }
+
+    #[test]
+    fn under_collateralized_liquidation_socializes_only_the_uninsured_loss() {
+        let mut pool = pool(5_000, 2_300, 300);
+        let (mut vault_balance, mut flt_supply) = (700, 1_000);
+        assert_eq!(exchange_rate(vault_balance, &pool, flt_supply).unwrap(), 2 * EXCHANGE_RATE_SCALE);
+
+        // 400 escrowed FLT (worth 800) against 2,000 principal, applied as `liquidate` does.
+        let quote = liquidation(&loan(2_000, 0), &staker(400), &config(), vault_balance, &pool, flt_supply).unwrap();
+        assert_eq!(pool.write_off(2_000, quote.shortfall).unwrap(), (quote.from_insurance, quote.socialized));
+        flt_supply -= quote.seized;
+        vault_balance = vault_balance - quote.bonus + quote.from_insurance;
+
+        assert_eq!(pool.insurance_fund, 0);
+        assert_eq!(pool.bad_debt, 902);
+        assert_eq!(pool.active_loan_total, 300);
+        // The other 600 FLT backed 3,000 - 800 = 2,200 and now back 1,298: they lost exactly the
+        // socialized 902, and the rate falls from 2.0 to (1,298 + 1,000) / (600 + 1,000).
+        assert_eq!(vault_assets(vault_balance, &pool).unwrap(), 2_200 - 902);
+        assert_eq!(exchange_rate(vault_balance, &pool, flt_supply).unwrap(), 1_436_250_000);
+    }
+
+    #[test]
+    fn max_borrow_values_the_escrow_at_the_exchange_rate() {
+        // 900 on hand + 500 lent = 1,400 backing 200 FLT; with the virtual 1,000 on each side
+        // that is 2 collateral per FLT. The 100 insurance is held apart.
+        let pool = pool(1_000, 500, 100);
+        let mut staker = staker(300);
+        staker.staked_amount = 450; // boosted weight does not count
+        assert_eq!(max_borrow(&staker, &config(), 900, &pool, 200).unwrap(), 300);
+        staker.borrowed = 250;
+        assert_eq!(max_borrow(&staker, &config(), 900, &pool, 200).unwrap(), 50);
+        staker.borrowed = 400;
+        assert_eq!(max_borrow(&staker, &config(), 900, &pool, 200).unwrap(), 0);
+    }
+
+    #[test]
+    fn exchange_rate_excludes_reserves() {
+        let mut pool = pool(1_000, 500, 100);
+        pool.reserved_rewards = 400;
+        // 2,900 on hand + 500 lent - 400 reserved = 3,000 backing 1,000 FLT; with the virtual
+        // 1,000 on each side that is 2 collateral per FLT. The 100 insurance is held apart.
+        assert_eq!(exchange_rate(2_900, &pool, 1_000).unwrap(), 2 * EXCHANGE_RATE_SCALE);
+        assert_eq!(stake_shares(100, 2_900, &pool, 1_000).unwrap(), 50);
+        assert_eq!(unstake_assets(50, 2_900, &pool, 1_000).unwrap(), 100);
+    }
+}
@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer, MintTo};
-use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
-use pyth_sdk_solana::load_price_feed_from_account_info;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer, MintTo};
+use anchor_lang::solana_program::sysvar::instructions as ix_sysvar;
+use anchor_lang::Discriminator;
+
+pub mod math;
+pub mod oracle;
 
 declare_id!("BVN4FsG6E67eboE2nK6yHZkh7segTJ2KfQfiZhjPoQDk");
 
@@ -9,12 +12,120 @@ declare_id!("BVN4FsG6E67eboE2nK6yHZkh7segTJ2KfQfiZhjPoQDk");
 pub mod flash_liquidity_token {
     use super::*;
 
+    /// Initialize the protocol.
+    /// Creates the Governance PDA and records the signer as admin.
+    pub fn initialize(ctx: Context<Initialize>, params: GovernanceParams) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        let admin = ctx.accounts.admin.key();
+        governance.admin = admin;
+        governance.pending_admin = Pubkey::default();
+        governance.roles = GovernanceRoles {
+            fee_manager: admin,
+            risk_manager: admin,
+            listing_manager: admin,
+            guardian: admin,
+        };
+        params.validate()?;
+        governance.apply(params);
+        governance.bump = ctx.bumps.governance;
+
+        emit_governance_updated(governance, admin)
+    }
+
+    /// List a new collateral type.
+    /// Creates the collateral's risk configuration, the vault PDA, the vault token account,
+    /// the insurance fund's token account, the per-collateral FLT mint (with the program as mint
+    /// authority) and reward pool. Vault accounts left behind by an earlier `delist_collateral` are reused.
+    pub fn list_collateral(ctx: Context<ListCollateral>, params: CollateralParams) -> Result<()> {
+        params.fee_curve.validate()?;
+        let collateral_config = &mut ctx.accounts.collateral_config;
+        collateral_config.collateral_mint = ctx.accounts.collateral_mint.key();
+        collateral_config.status = CollateralStatus::Active;
+        collateral_config.apply(params);
+        collateral_config.bump = ctx.bumps.collateral_config;
+
+        // Record the bumps so later instructions can sign for the PDAs.
+        ctx.accounts.vault_account.bump = ctx.bumps.vault_account;
+        ctx.accounts.flt_mint_wrapper.bump = ctx.bumps.flt_mint;
+
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.collateral_mint = ctx.accounts.collateral_mint.key();
+        reward_pool.bump = ctx.bumps.reward_pool;
+
+        emit!(CollateralListed {
+            authority: ctx.accounts.listing_manager.key(),
+            collateral_mint: ctx.accounts.collateral_mint.key(),
+            params,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    /// Update a listed collateral's listing status. Listing manager only.
+    /// Only available while the timelock delay is zero; otherwise use `queue_parameter_change`.
+    pub fn update_collateral(ctx: Context<UpdateCollateral>, status: CollateralStatus) -> Result<()> {
+        require!(ctx.accounts.governance.timelock_delay_slots == 0, CustomError::TimelockActive);
+        let collateral_mint = ctx.accounts.collateral_config.collateral_mint;
+        apply_collateral_change(
+            ParameterChange::ListingStatus { collateral_mint, status },
+            ctx.accounts.listing_manager.key(),
+            &mut ctx.accounts.collateral_config,
+        )
+    }
+
+    /// Replace a listed collateral's fee curve. Fee manager only.
+    /// Only available while the timelock delay is zero; otherwise use `queue_parameter_change`.
+    pub fn set_collateral_fee_curve(ctx: Context<SetCollateralFeeCurve>, fee_curve: FeeCurve) -> Result<()> {
+        require!(ctx.accounts.governance.timelock_delay_slots == 0, CustomError::TimelockActive);
+        let collateral_mint = ctx.accounts.collateral_config.collateral_mint;
+        apply_collateral_change(
+            ParameterChange::CollateralFeeCurve { collateral_mint, fee_curve },
+            ctx.accounts.fee_manager.key(),
+            &mut ctx.accounts.collateral_config,
+        )
+    }
+
+    /// Replace a listed collateral's borrow ratio, liquidation penalty, borrow cap and price feed.
+    /// Risk manager only. Only available while the timelock delay is zero; otherwise use
+    /// `queue_parameter_change`.
+    pub fn set_collateral_risk(ctx: Context<SetCollateralRisk>, params: CollateralRiskParams) -> Result<()> {
+        require!(ctx.accounts.governance.timelock_delay_slots == 0, CustomError::TimelockActive);
+        let collateral_mint = ctx.accounts.collateral_config.collateral_mint;
+        apply_collateral_change(
+            ParameterChange::CollateralRisk { collateral_mint, params },
+            ctx.accounts.risk_manager.key(),
+            &mut ctx.accounts.collateral_config,
+        )
+    }
+
+    /// Delist a collateral by closing its configuration.
+    /// Only possible once every FLT has been redeemed and no loans remain open.
+    pub fn delist_collateral(ctx: Context<DelistCollateral>) -> Result<()> {
+        require!(
+            ctx.accounts.flt_mint.supply == 0 && ctx.accounts.reward_pool.active_loan_total == 0,
+            CustomError::CollateralNotEmpty
+        );
+        emit!(CollateralDelisted {
+            authority: ctx.accounts.listing_manager.key(),
+            collateral_mint: ctx.accounts.collateral_mint.key(),
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
     /// Stake collateral to mint FLT tokens.
     /// The staker specifies the amount and lock duration (in slots).
-    /// Early stakers (when total staked < 10,000 SOL) receive a 1.5x boost.
+    /// FLT is minted at the current exchange rate of vault assets to FLT supply, into the staker's
+    /// escrow held by the program; the lock, reward weight and borrowing power follow the escrow.
+    /// A new lock never ends before an existing one.
+    /// Early stakers (when the vault holds < 10,000 whole tokens of the collateral) receive a 1.5x boost.
     pub fn stake(ctx: Context<Stake>, amount: u64, lock_duration: u64) -> Result<()> {
         let clock = Clock::get()?;
         let current_slot = clock.slot;
+        ctx.accounts.governance.require_not_paused(PAUSE_STAKE)?;
+
+        // Reentrancy protection.
+        require!(!ctx.accounts.reward_pool.flash_loan_active, CustomError::ReentrancyDetected);
 
         // Validate collateral mint.
         require!(
@@ -22,10 +133,15 @@ pub mod flash_liquidity_token {
             CustomError::InvalidCollateralMint
         );
         require!(
-            ctx.accounts.governance.supported_collaterals.contains(&ctx.accounts.collateral_mint.key()),
-            CustomError::UnsupportedCollateral
+            ctx.accounts.collateral_config.status == CollateralStatus::Active,
+            CustomError::CollateralNotActive
         );
 
+        // Price the deposit against vault assets before they change.
+        let assets = vault_assets(&ctx.accounts.vault_token_account, &ctx.accounts.reward_pool)?;
+        let flt_amount = math::shares_for_deposit(amount, assets, ctx.accounts.flt_mint.supply)?;
+        require!(flt_amount > 0, CustomError::InsufficientStakedAmount);
+
         // Transfer collateral from the user to the vault.
         let transfer_cpi_accounts = Transfer {
             from: ctx.accounts.user_token_account.to_account_info(),
@@ -37,45 +153,60 @@ pub mod flash_liquidity_token {
             amount,
         )?;
 
-        // Mint FLT tokens to the user.
-        let seeds = &[ctx.accounts.flt_mint.to_account_info().key.as_ref(), &[ctx.accounts.flt_mint_wrapper.bump]];
+        // Mint FLT tokens into the staker's escrow.
+        let collateral_mint_key = ctx.accounts.collateral_mint.key();
+        let seeds = &[b"flt_mint", collateral_mint_key.as_ref(), &[ctx.accounts.flt_mint_wrapper.bump]];
         let signer = &[&seeds[..]];
         let mint_to_cpi_accounts = MintTo {
             mint: ctx.accounts.flt_mint.to_account_info(),
-            to: ctx.accounts.user_flt_token_account.to_account_info(),
+            to: ctx.accounts.stake_escrow.to_account_info(),
             authority: ctx.accounts.flt_mint.to_account_info(),
         };
         token::mint_to(
             CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), mint_to_cpi_accounts, signer),
-            amount,
+            flt_amount,
         )?;
 
         // Reward Boosting for Early Adopters:
-        // If the total staked is below 10,000 SOL, apply a 1.5x multiplier.
-        let boosted_amount = if ctx.accounts.reward_pool.total_staked < 10_000 * LAMPORTS_PER_SOL {
-            amount.checked_mul(150).unwrap().checked_div(100).unwrap()
-        } else {
-            amount
-        };
+        // If the vault held less than 10,000 whole tokens before this deposit, apply a 1.5x
+        // multiplier. Reward weight leaves with the escrowed FLT in proportion, boost included.
+        let early_adopter_threshold = math::early_adopter_threshold(ctx.accounts.collateral_mint.decimals)?;
+        let boosted_amount = math::boosted_weight(flt_amount, assets, early_adopter_threshold)?;
 
-        // Update or initialize the staker record.
+        // Update or initialize the staker record, settling fees earned by the previous weight.
+        let acc_fee_per_share = ctx.accounts.reward_pool.acc_fee_per_share;
         let staker = &mut ctx.accounts.staker;
-        staker.staked_amount = staker.staked_amount.checked_add(boosted_amount).unwrap();
+        staker.settle_rewards(acc_fee_per_share)?;
+        staker.staked_amount = math::add(staker.staked_amount, boosted_amount)?;
+        staker.escrowed_flt = math::add(staker.escrowed_flt, flt_amount)?;
+        staker.sync_reward_debt(acc_fee_per_share)?;
         staker.collateral_mint = ctx.accounts.collateral_mint.key();
         staker.last_compound_slot = current_slot;
-        staker.lock_end_slot = current_slot.checked_add(lock_duration).unwrap();
+        staker.lock_end_slot = staker.lock_end_slot.max(math::add(current_slot, lock_duration)?);
 
         // Update the global reward pool.
-        ctx.accounts.reward_pool.total_staked = ctx.accounts.reward_pool.total_staked.checked_add(boosted_amount).unwrap();
-        ctx.accounts.reward_pool.update_counter = ctx.accounts.reward_pool.update_counter.checked_add(1).unwrap();
+        ctx.accounts.reward_pool.total_staked = math::add(ctx.accounts.reward_pool.total_staked, boosted_amount)?;
+        ctx.accounts.reward_pool.update_counter = math::add(ctx.accounts.reward_pool.update_counter, 1)?;
+
+        emit!(Staked {
+            user: ctx.accounts.user.key(),
+            collateral_mint: ctx.accounts.collateral_mint.key(),
+            amount,
+            flt_minted: flt_amount,
+            reward_weight: boosted_amount,
+            lock_end_slot: ctx.accounts.staker.lock_end_slot,
+            slot: current_slot,
+        });
 
         Ok(())
     }
 
     /// Borrow liquidity for a short duration.
     /// The dynamic flash loan fee is computed based on utilization and adjusted via the Pyth oracle.
-    /// After transferring funds, the program calls a callback program for atomic arbitrage.
-    pub fn borrow(ctx: Context<Borrow>, amount: u64, loan_duration: u64) -> Result<()> {
+    /// No callback is invoked; atomic callbacks go through `flash_loan`.
+    /// In `Flash` mode a matching `repay` must follow in the same transaction; in `Term` mode
+    /// the loan is bounded by the staker's collateral and due after `loan_duration` slots.
+    pub fn borrow(ctx: Context<Borrow>, amount: u64, loan_duration: u64, mode: LoanMode) -> Result<()> {
         let clock = Clock::get()?;
         let current_slot = clock.slot;
         let current_time_i64 = clock.unix_timestamp;
@@ -83,69 +214,88 @@ pub mod flash_liquidity_token {
         // Ensure the timestamp is non-negative before conversion.
         require!(current_time_i64 >= 0, CustomError::InvalidTimestamp);
         let current_time: u64 = current_time_i64 as u64;
+        ctx.accounts.governance.require_not_paused(PAUSE_BORROW)?;
 
         // Reentrancy protection.
         require!(!ctx.accounts.loan.active, CustomError::ReentrancyDetected);
+        require!(!ctx.accounts.reward_pool.flash_loan_active, CustomError::ReentrancyDetected);
 
-        // Compute utilization.
-        let new_utilization = ctx.accounts
-            .reward_pool
-            .active_loan_total
-            .checked_add(amount)
-            .unwrap()
-            .checked_mul(100)
-            .unwrap()
-            .checked_div(ctx.accounts.reward_pool.total_staked)
-            .unwrap();
-        let mut flash_fee_bps: u64 = if new_utilization < 20 {
-            15  // 0.15%
-        } else if new_utilization < 80 {
-            20  // 0.20%
-        } else {
-            50  // 0.50%
-        };
+        // Listing status and borrow cap for this collateral.
+        let collateral_config = &ctx.accounts.collateral_config;
+        require!(collateral_config.status == CollateralStatus::Active, CustomError::CollateralNotActive);
+        require!(
+            math::add(ctx.accounts.reward_pool.active_loan_total, amount)? <= collateral_config.borrow_cap,
+            CustomError::BorrowCapExceeded
+        );
 
-        // Oracle Integration: Read Pyth price to adjust the fee.
-        let price_feed = load_price_feed_from_account_info(&ctx.accounts.pyth_price)
-            .map_err(|_| ProgramError::Custom(CustomError::OraclePriceUnavailable as u32))?;
-        // Use get_price_no_older_than with a 60-second threshold and the current timestamp.
-        let price_info = price_feed
-            .get_price_no_older_than(60, current_time)
-            .ok_or(ProgramError::Custom(CustomError::OraclePriceUnavailable as u32))?;
-        if price_info.price > 0 {
-            flash_fee_bps = flash_fee_bps
-                .checked_mul(100)
-                .unwrap()
-                .checked_div(price_info.price as u64)
-                .unwrap();
+        // Flash mode: the repayment must already be part of this transaction.
+        if mode == LoanMode::Flash {
+            require_flash_repay(
+                &ctx.accounts.instructions_sysvar,
+                ctx.accounts.loan.key(),
+                ctx.accounts.vault_token_account.key(),
+                amount,
+            )?;
         }
 
-        let flash_fee = amount.checked_mul(flash_fee_bps).unwrap().checked_div(10000).unwrap();
-        let amount_after_fee = amount.checked_sub(flash_fee).unwrap();
+        let price = collateral_price(
+            &ctx.accounts.collateral_config,
+            &ctx.accounts.pyth_price,
+            ctx.accounts.secondary_price.as_ref(),
+            current_time,
+        )?;
+        let utilization_bps =
+            post_borrow_utilization_bps(&ctx.accounts.vault_token_account, &ctx.accounts.reward_pool, amount)?;
+        let flash_fee_bps = dynamic_fee_bps(&ctx.accounts.collateral_config, utilization_bps, price)?;
+        let flash_fee = math::fee_amount(amount, flash_fee_bps)?;
+        let amount_after_fee = math::sub(amount, flash_fee)?;
 
-        // Enforce collateralized borrowing: loan amount must be within allowed ratio.
+        // Enforce collateralized borrowing for term loans: all outstanding term principal must stay
+        // within the allowed ratio of the escrowed FLT's collateral value, priced before any transfer.
         let staker = &ctx.accounts.staker;
-        require!(
-            amount <= staker
-                .staked_amount
-                .checked_mul(ctx.accounts.governance.max_borrow_ratio)
-                .unwrap()
-                .checked_div(10000)
-                .unwrap(),
-            CustomError::BorrowAmountExceedsCollateral
-        );
+        if mode == LoanMode::Term {
+            let escrow_value = math::assets_for_shares(
+                staker.escrowed_flt,
+                vault_assets(&ctx.accounts.vault_token_account, &ctx.accounts.reward_pool)?,
+                ctx.accounts.flt_mint.supply,
+            )?;
+            require!(
+                math::add(staker.borrowed, amount)?
+                    <= math::max_borrow(escrow_value, ctx.accounts.collateral_config.max_borrow_ratio)?,
+                CustomError::BorrowAmountExceedsCollateral
+            );
+        }
 
         // Record loan details and mark active.
+        let borrower_state = &mut ctx.accounts.borrower_state;
         let loan = &mut ctx.accounts.loan;
         loan.borrower = ctx.accounts.borrower.key();
+        loan.collateral_mint = staker.collateral_mint;
+        loan.nonce = borrower_state.next_loan_nonce;
+        loan.bump = ctx.bumps.loan;
         loan.amount = amount;
         loan.start_slot = current_slot;
-        loan.due_slot = current_slot.checked_add(loan_duration).unwrap();
+        loan.due_slot = match mode {
+            LoanMode::Flash => current_slot,
+            LoanMode::Term => math::add(current_slot, loan_duration)?,
+        };
         loan.active = true;
+        loan.mode = mode;
+
+        // Advance the nonce so the borrower can open further concurrent loans.
+        borrower_state.bump = ctx.bumps.borrower_state;
+        borrower_state.next_loan_nonce = math::add(borrower_state.next_loan_nonce, 1)?;
+        borrower_state.open_loans = math::add(borrower_state.open_loans, 1)?;
+
+        // Term principal keeps the staker's escrow locked until it is repaid or liquidated.
+        let staker = &mut ctx.accounts.staker;
+        if mode == LoanMode::Term {
+            staker.borrowed = math::add(staker.borrowed, amount)?;
+        }
 
         // Update active loan total in reward pool.
-        ctx.accounts.reward_pool.active_loan_total = ctx.accounts.reward_pool.active_loan_total.checked_add(amount).unwrap();
-        ctx.accounts.reward_pool.update_counter = ctx.accounts.reward_pool.update_counter.checked_add(1).unwrap();
+        ctx.accounts.reward_pool.active_loan_total = math::add(ctx.accounts.reward_pool.active_loan_total, amount)?;
+        ctx.accounts.reward_pool.update_counter = math::add(ctx.accounts.reward_pool.update_counter, 1)?;
 
         // Transfer liquidity from the vault to the borrower.
         let seeds = &[b"vault", staker.collateral_mint.as_ref(), &[ctx.accounts.vault_account.bump]];
@@ -160,53 +310,184 @@ pub mod flash_liquidity_token {
             amount_after_fee,
         )?;
 
-        // Credit the flash fee to the reward pool.
-        ctx.accounts.reward_pool.accrued_fees = ctx.accounts.reward_pool.accrued_fees.checked_add(flash_fee).unwrap();
+        // Credit the flash fee to the reward pool and move its insurance share out of the vault.
+        let insurance = credit_fee(
+            &mut ctx.accounts.reward_pool,
+            flash_fee,
+            &ctx.accounts.governance,
+        )?;
+        let transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            to: ctx.accounts.insurance_token_account.to_account_info(),
+            authority: ctx.accounts.vault_account.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts, signer),
+            insurance,
+        )?;
+
+        emit!(Borrowed {
+            borrower: ctx.accounts.borrower.key(),
+            collateral_mint: ctx.accounts.loan.collateral_mint,
+            loan: Some(ctx.accounts.loan.key()),
+            mode,
+            amount,
+            fee: flash_fee,
+            fee_bps: flash_fee_bps,
+            utilization_bps,
+            oracle_price: price,
+            due_slot: ctx.accounts.loan.due_slot,
+            slot: current_slot,
+        });
+
+        Ok(())
+    }
+
+    /// Execute a flash loan in a single instruction.
+    /// Transfers `amount` to the borrower, invokes the callback program with `callback_data` and
+    /// all remaining accounts, then requires the vault to have received principal plus fee.
+    pub fn flash_loan<'info>(
+        ctx: Context<'_, '_, '_, 'info, FlashLoan<'info>>,
+        amount: u64,
+        callback_data: Vec<u8>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let current_time_i64 = clock.unix_timestamp;
+
+        // Ensure the timestamp is non-negative before conversion.
+        require!(current_time_i64 >= 0, CustomError::InvalidTimestamp);
+        let current_time: u64 = current_time_i64 as u64;
+        ctx.accounts.governance.require_not_paused(PAUSE_BORROW)?;
+
+        // Reentrancy protection.
+        require!(!ctx.accounts.reward_pool.flash_loan_active, CustomError::ReentrancyDetected);
+
+        // Listing status and borrow cap for this collateral.
+        let collateral_config = &ctx.accounts.collateral_config;
+        require!(collateral_config.status == CollateralStatus::Active, CustomError::CollateralNotActive);
+        require!(
+            math::add(ctx.accounts.reward_pool.active_loan_total, amount)? <= collateral_config.borrow_cap,
+            CustomError::BorrowCapExceeded
+        );
+
+        let price = collateral_price(
+            &ctx.accounts.collateral_config,
+            &ctx.accounts.pyth_price,
+            ctx.accounts.secondary_price.as_ref(),
+            current_time,
+        )?;
+        let utilization_bps =
+            post_borrow_utilization_bps(&ctx.accounts.vault_token_account, &ctx.accounts.reward_pool, amount)?;
+        let flash_fee_bps = dynamic_fee_bps(&ctx.accounts.collateral_config, utilization_bps, price)?;
+        let flash_fee = math::fee_amount(amount, flash_fee_bps)?;
+
+        // Transfer liquidity from the vault to the borrower.
+        let collateral_mint_key = ctx.accounts.collateral_mint.key();
+        let seeds = &[b"vault", collateral_mint_key.as_ref(), &[ctx.accounts.vault_account.bump]];
+        let signer = &[&seeds[..]];
+        let transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            to: ctx.accounts.borrower_token_account.to_account_info(),
+            authority: ctx.accounts.vault_account.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts, signer),
+            amount,
+        )?;
+        ctx.accounts.vault_token_account.reload()?;
+        let balance_after_transfer = ctx.accounts.vault_token_account.amount;
+
+        // Lock the pool for the duration of the callback so it cannot re-enter this program
+        // (e.g. stake or repay with the borrowed funds) to fake the repayment.
+        ctx.accounts.reward_pool.flash_loan_active = true;
+        ctx.accounts.reward_pool.exit(&crate::ID)?;
 
         // Flash Loan Callback:
-        // After transferring liquidity, invoke the callback program.
+        // Forward the caller-supplied data and every remaining account to the callback program.
         let callback_ix = anchor_lang::solana_program::instruction::Instruction {
             program_id: ctx.accounts.callback_program.key(),
-            accounts: vec![
-                AccountMeta::new(ctx.accounts.borrower.key(), true),
-                AccountMeta::new(ctx.accounts.borrower_token_account.key(), false),
-            ],
-            data: vec![], // Insert callback-specific data here.
+            accounts: ctx
+                .remaining_accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.key(),
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: callback_data,
+        };
+        let mut callback_account_infos = ctx.remaining_accounts.to_vec();
+        callback_account_infos.push(ctx.accounts.callback_program.to_account_info());
+        anchor_lang::solana_program::program::invoke(&callback_ix, &callback_account_infos)?;
+
+        // Verify the vault was repaid principal plus fee.
+        ctx.accounts.vault_token_account.reload()?;
+        let required_balance = math::add(math::add(balance_after_transfer, amount)?, flash_fee)?;
+        require!(
+            ctx.accounts.vault_token_account.amount >= required_balance,
+            CustomError::RepaymentFeeMissing
+        );
+
+        // Release the lock, credit the flash fee to the reward pool and move its insurance share
+        // out of the vault.
+        ctx.accounts.reward_pool.reload()?;
+        ctx.accounts.reward_pool.flash_loan_active = false;
+        let insurance = credit_fee(
+            &mut ctx.accounts.reward_pool,
+            flash_fee,
+            &ctx.accounts.governance,
+        )?;
+        let transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            to: ctx.accounts.insurance_token_account.to_account_info(),
+            authority: ctx.accounts.vault_account.to_account_info(),
         };
-        anchor_lang::solana_program::program::invoke(
-            &callback_ix,
-            &[
-                ctx.accounts.callback_program.to_account_info(),
-                ctx.accounts.borrower.to_account_info(),
-                ctx.accounts.borrower_token_account.to_account_info(),
-            ],
+        token::transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts, signer),
+            insurance,
         )?;
+        ctx.accounts.reward_pool.update_counter = math::add(ctx.accounts.reward_pool.update_counter, 1)?;
+
+        emit!(Borrowed {
+            borrower: ctx.accounts.borrower.key(),
+            collateral_mint: collateral_mint_key,
+            loan: None,
+            mode: LoanMode::Flash,
+            amount,
+            fee: flash_fee,
+            fee_bps: flash_fee_bps,
+            utilization_bps,
+            oracle_price: price,
+            due_slot: clock.slot,
+            slot: clock.slot,
+        });
 
         Ok(())
     }
 
-    /// Repay the borrowed liquidity.
-    /// If repaid late, a penalty fee is applied.
+    /// Repay the borrowed liquidity, in full or in part.
+    /// If repaid late, a penalty fee is applied to the principal being repaid.
+    /// A partial repayment reduces the loan's principal and keeps it open; the loan is
+    /// closed once the principal reaches zero. Anything paid beyond the amount owed is not taken.
     pub fn repay(ctx: Context<Repay>, amount: u64) -> Result<()> {
         let clock = Clock::get()?;
         let current_slot = clock.slot;
+        ctx.accounts.governance.require_not_paused(PAUSE_REPAY)?;
         let loan = &mut ctx.accounts.loan;
 
         require!(loan.active, CustomError::LoanNotActive);
+        require!(!ctx.accounts.reward_pool.flash_loan_active, CustomError::ReentrancyDetected);
 
-        let mut penalty_fee: u64 = 0;
-        if current_slot > loan.due_slot {
-            let overdue_slots = current_slot.checked_sub(loan.due_slot).unwrap();
-            penalty_fee = loan.amount
-                .checked_mul(overdue_slots)
-                .unwrap()
-                .checked_mul(ctx.accounts.governance.liquidation_penalty_bps)
-                .unwrap()
-                .checked_div(10000)
-                .unwrap();
-        }
-        let total_required = loan.amount.checked_add(penalty_fee).unwrap();
-        require!(amount >= total_required, CustomError::RepaymentFeeMissing);
+        let overdue_slots = current_slot.saturating_sub(loan.due_slot);
+        let (principal_repaid, penalty_fee) = math::split_repayment(
+            amount,
+            loan.amount,
+            overdue_slots,
+            ctx.accounts.collateral_config.liquidation_penalty_bps,
+        )?;
+        require!(principal_repaid > 0, CustomError::RepaymentFeeMissing);
+        let amount_paid = math::add(principal_repaid, penalty_fee)?;
 
         let transfer_cpi_accounts = Transfer {
             from: ctx.accounts.borrower_token_account.to_account_info(),
@@ -215,42 +496,197 @@ pub mod flash_liquidity_token {
         };
         token::transfer(
             CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts),
-            amount,
+            amount_paid,
         )?;
 
-        ctx.accounts.reward_pool.active_loan_total = ctx.accounts.reward_pool.active_loan_total.checked_sub(loan.amount).unwrap();
+        ctx.accounts.reward_pool.active_loan_total =
+            math::sub(ctx.accounts.reward_pool.active_loan_total, principal_repaid)?;
+        if loan.mode == LoanMode::Term {
+            ctx.accounts.staker.borrowed = math::sub(ctx.accounts.staker.borrowed, principal_repaid)?;
+        }
         if penalty_fee > 0 {
-            ctx.accounts.reward_pool.accrued_fees = ctx.accounts.reward_pool.accrued_fees.checked_add(penalty_fee).unwrap();
+            let insurance = credit_fee(
+                &mut ctx.accounts.reward_pool,
+                penalty_fee,
+                &ctx.accounts.governance,
+            )?;
+            let seeds = &[b"vault", loan.collateral_mint.as_ref(), &[ctx.accounts.vault_account.bump]];
+            let signer = &[&seeds[..]];
+            let transfer_cpi_accounts = Transfer {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                to: ctx.accounts.insurance_token_account.to_account_info(),
+                authority: ctx.accounts.vault_account.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts, signer),
+                insurance,
+            )?;
         }
-        loan.active = false;
-        ctx.accounts.reward_pool.update_counter = ctx.accounts.reward_pool.update_counter.checked_add(1).unwrap();
+        loan.amount = math::sub(loan.amount, principal_repaid)?;
+        ctx.accounts.reward_pool.update_counter = math::add(ctx.accounts.reward_pool.update_counter, 1)?;
+
+        emit!(Repaid {
+            borrower: ctx.accounts.borrower.key(),
+            collateral_mint: loan.collateral_mint,
+            loan: loan.key(),
+            principal: principal_repaid,
+            penalty: penalty_fee,
+            amount: amount_paid,
+            remaining_principal: loan.amount,
+            slot: current_slot,
+        });
+
+        // Close the loan record once fully repaid.
+        if loan.amount == 0 {
+            ctx.accounts.borrower_state.open_loans = math::sub(ctx.accounts.borrower_state.open_loans, 1)?;
+            ctx.accounts.loan.close(ctx.accounts.borrower.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    /// Push a term loan's due slot forward by `extension_slots`.
+    /// The borrower pays the same fee as reopening the loan now: the current fee curve at the
+    /// pool's current utilization, adjusted by the oracle price, on the outstanding principal.
+    /// Only loans that are not yet overdue can be extended.
+    pub fn extend_loan(ctx: Context<ExtendLoan>, extension_slots: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let current_slot = clock.slot;
+        let current_time_i64 = clock.unix_timestamp;
+        require!(current_time_i64 >= 0, CustomError::InvalidTimestamp);
+        ctx.accounts.governance.require_not_paused(PAUSE_BORROW)?;
+        require!(!ctx.accounts.reward_pool.flash_loan_active, CustomError::ReentrancyDetected);
+
+        let loan = &ctx.accounts.loan;
+        require!(loan.active, CustomError::LoanNotActive);
+        require!(loan.mode == LoanMode::Term, CustomError::LoanNotExtendable);
+        require!(current_slot <= loan.due_slot, CustomError::LoanOverdue);
+        require!(
+            ctx.accounts.collateral_config.status == CollateralStatus::Active,
+            CustomError::CollateralNotActive
+        );
+
+        let price = collateral_price(
+            &ctx.accounts.collateral_config,
+            &ctx.accounts.pyth_price,
+            ctx.accounts.secondary_price.as_ref(),
+            current_time_i64 as u64,
+        )?;
+        let utilization_bps =
+            post_borrow_utilization_bps(&ctx.accounts.vault_token_account, &ctx.accounts.reward_pool, 0)?;
+        let fee_bps = dynamic_fee_bps(&ctx.accounts.collateral_config, utilization_bps, price)?;
+        let fee = math::fee_amount(loan.amount, fee_bps)?;
+
+        let transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.borrower_token_account.to_account_info(),
+            to: ctx.accounts.vault_token_account.to_account_info(),
+            authority: ctx.accounts.borrower.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts),
+            fee,
+        )?;
+        let insurance = credit_fee(
+            &mut ctx.accounts.reward_pool,
+            fee,
+            &ctx.accounts.governance,
+        )?;
+        let seeds = &[b"vault", loan.collateral_mint.as_ref(), &[ctx.accounts.vault_account.bump]];
+        let signer = &[&seeds[..]];
+        let transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            to: ctx.accounts.insurance_token_account.to_account_info(),
+            authority: ctx.accounts.vault_account.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts, signer),
+            insurance,
+        )?;
+        ctx.accounts.reward_pool.update_counter = math::add(ctx.accounts.reward_pool.update_counter, 1)?;
+
+        let loan = &mut ctx.accounts.loan;
+        loan.due_slot = math::add(loan.due_slot, extension_slots)?;
+
+        emit!(LoanExtended {
+            borrower: loan.borrower,
+            collateral_mint: loan.collateral_mint,
+            loan: loan.key(),
+            principal: loan.amount,
+            fee,
+            fee_bps,
+            utilization_bps,
+            oracle_price: price,
+            due_slot: loan.due_slot,
+            slot: current_slot,
+        });
 
         Ok(())
     }
 
     /// Liquidate an overdue loan.
-    /// If a loan is past its due slot plus a grace period, a liquidator can seize collateral.
+    /// If a loan is past its due slot plus a grace period, a liquidator can seize the borrower's
+    /// staked position: escrowed FLT worth up to principal plus penalty, at the current exchange
+    /// rate, is burned together with its share of reward weight. The liquidator's bonus (the
+    /// penalty) is paid in collateral out of the seized FLT's value; the rest covers the principal.
+    /// Principal the seized position cannot cover runs through the loss waterfall: the insurance
+    /// fund pays it back into the vault first, and the rest is recorded as bad debt and socialized
+    /// across FLT holders through the exchange rate. The loan record is closed to the borrower.
     pub fn liquidate(ctx: Context<Liquidate>) -> Result<()> {
         let clock = Clock::get()?;
         let current_slot = clock.slot;
-        let loan = &mut ctx.accounts.loan;
+        let current_time_i64 = clock.unix_timestamp;
+        require!(current_time_i64 >= 0, CustomError::InvalidTimestamp);
+        ctx.accounts.governance.require_not_paused(PAUSE_LIQUIDATE)?;
+
+        // Refuse to liquidate while the collateral's price sources are stale or disagree.
+        let price = collateral_price(
+            &ctx.accounts.collateral_config,
+            &ctx.accounts.pyth_price,
+            ctx.accounts.secondary_price.as_ref(),
+            current_time_i64 as u64,
+        )?;
+        let loan = &ctx.accounts.loan;
+
+        // Reentrancy protection.
+        require!(!ctx.accounts.reward_pool.flash_loan_active, CustomError::ReentrancyDetected);
+        require!(loan.active, CustomError::LoanNotActive);
 
         // Ensure the loan is overdue (including grace period).
         require!(
-            current_slot > loan.due_slot.checked_add(ctx.accounts.governance.liquidation_grace_slots).unwrap(),
+            current_slot > math::add(loan.due_slot, ctx.accounts.governance.liquidation_grace_slots)?,
             CustomError::LoanNotOverdue
         );
 
-        // Calculate penalty collateral (as an incentive to liquidators).
-        let penalty_collateral = loan.amount
-            .checked_mul(ctx.accounts.governance.liquidation_penalty_bps)
-            .unwrap()
-            .checked_div(10000)
-            .unwrap();
+        // Seize escrowed FLT worth principal plus penalty (the liquidator's incentive), priced
+        // before the FLT supply or the vault changes.
+        let penalty = math::liquidation_bonus(loan.amount, ctx.accounts.collateral_config.liquidation_penalty_bps)?;
+        let (seized, bonus, shortfall) = math::liquidate_escrow(
+            loan.amount,
+            penalty,
+            ctx.accounts.staker.escrowed_flt,
+            vault_assets(&ctx.accounts.vault_token_account, &ctx.accounts.reward_pool)?,
+            ctx.accounts.flt_mint.supply,
+        )?;
+        let acc_fee_per_share = ctx.accounts.reward_pool.acc_fee_per_share;
+        let staker = &mut ctx.accounts.staker;
+        if loan.mode == LoanMode::Term {
+            staker.borrowed = math::sub(staker.borrowed, loan.amount)?;
+        }
+        let weight_removed = staker.remove_escrow(seized, acc_fee_per_share)?;
+        ctx.accounts.reward_pool.total_staked = math::sub(ctx.accounts.reward_pool.total_staked, weight_removed)?;
 
-        // Transfer penalty collateral from the vault to the liquidator.
-        let seeds = &[b"vault", ctx.accounts.staker.collateral_mint.as_ref(), &[ctx.accounts.vault_account.bump]];
+        // Burn the seized FLT and pay the bonus out of its value.
+        let seeds = &[b"vault", loan.collateral_mint.as_ref(), &[ctx.accounts.vault_account.bump]];
         let signer = &[&seeds[..]];
+        let burn_cpi_accounts = Burn {
+            mint: ctx.accounts.flt_mint.to_account_info(),
+            from: ctx.accounts.stake_escrow.to_account_info(),
+            authority: ctx.accounts.vault_account.to_account_info(),
+        };
+        token::burn(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), burn_cpi_accounts, signer),
+            seized,
+        )?;
         let transfer_cpi_accounts = Transfer {
             from: ctx.accounts.vault_token_account.to_account_info(),
             to: ctx.accounts.liquidator_token_account.to_account_info(),
@@ -258,56 +694,128 @@ pub mod flash_liquidity_token {
         };
         token::transfer(
             CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts, signer),
-            penalty_collateral,
+            bonus,
         )?;
 
-        // Mark the loan as inactive and update global state.
-        loan.active = false;
-        ctx.accounts.reward_pool.active_loan_total = ctx.accounts.reward_pool.active_loan_total.checked_sub(loan.amount).unwrap();
-        ctx.accounts.reward_pool.accrued_fees = ctx.accounts.reward_pool.accrued_fees.checked_add(penalty_collateral).unwrap();
-        ctx.accounts.reward_pool.update_counter = ctx.accounts.reward_pool.update_counter.checked_add(1).unwrap();
+        // Write off the principal. Loss waterfall: the insurance fund pays back into the vault
+        // first, and the rest lowers the exchange rate.
+        ctx.accounts.borrower_state.open_loans = math::sub(ctx.accounts.borrower_state.open_loans, 1)?;
+        let (from_insurance, socialized) = ctx.accounts.reward_pool.write_off(loan.amount, shortfall)?;
+        ctx.accounts.reward_pool.update_counter = math::add(ctx.accounts.reward_pool.update_counter, 1)?;
+        if shortfall > 0 {
+            let transfer_cpi_accounts = Transfer {
+                from: ctx.accounts.insurance_token_account.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: ctx.accounts.vault_account.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts, signer),
+                from_insurance,
+            )?;
+            let reward_pool = &ctx.accounts.reward_pool;
+            emit!(BadDebtRecorded {
+                collateral_mint: loan.collateral_mint,
+                loan: loan.key(),
+                shortfall,
+                from_insurance,
+                socialized,
+                insurance_fund: reward_pool.insurance_fund,
+                bad_debt: reward_pool.bad_debt,
+                slot: current_slot,
+            });
+        }
+
+        emit!(Liquidated {
+            liquidator: ctx.accounts.liquidator.key(),
+            borrower: loan.borrower,
+            collateral_mint: loan.collateral_mint,
+            loan: loan.key(),
+            principal: loan.amount,
+            seized,
+            bonus,
+            oracle_price: price,
+            slot: current_slot,
+        });
 
+        ctx.accounts.loan.close(ctx.accounts.borrower.to_account_info())?;
         Ok(())
     }
 
     /// Compound rewards for a staker.
     /// Additional rewards are calculated based on slots elapsed since the last compounding.
     pub fn compound_rewards(ctx: Context<CompoundRewards>) -> Result<()> {
+        ctx.accounts.governance.require_not_paused(PAUSE_COMPOUND)?;
         let clock = Clock::get()?;
         let current_slot = clock.slot;
+        let acc_fee_per_share = ctx.accounts.reward_pool.acc_fee_per_share;
         let staker = &mut ctx.accounts.staker;
-        let slots_passed = current_slot.checked_sub(staker.last_compound_slot).unwrap();
-        let rate_numerator = ctx.accounts.governance.compound_rate_numerator;
-        let rate_denominator = ctx.accounts.governance.compound_rate_denominator;
-        let additional_rewards = staker
-            .staked_amount
-            .checked_mul(rate_numerator)
-            .unwrap()
-            .checked_mul(slots_passed)
-            .unwrap()
-            .checked_div(rate_denominator)
-            .unwrap();
-        staker.staked_amount = staker.staked_amount.checked_add(additional_rewards).unwrap();
+        staker.settle_rewards(acc_fee_per_share)?;
+        let slots_passed = math::sub(current_slot, staker.last_compound_slot)?;
+        let additional_rewards = math::compound_rewards(
+            staker.staked_amount,
+            ctx.accounts.governance.compound_rate_numerator,
+            ctx.accounts.governance.compound_rate_denominator,
+            slots_passed,
+        )?;
+        staker.staked_amount = math::add(staker.staked_amount, additional_rewards)?;
+        staker.sync_reward_debt(acc_fee_per_share)?;
         staker.last_compound_slot = current_slot;
-        ctx.accounts.reward_pool.total_staked = ctx.accounts.reward_pool.total_staked.checked_add(additional_rewards).unwrap();
-        ctx.accounts.reward_pool.update_counter = ctx.accounts.reward_pool.update_counter.checked_add(1).unwrap();
+        ctx.accounts.reward_pool.total_staked = math::add(ctx.accounts.reward_pool.total_staked, additional_rewards)?;
+        ctx.accounts.reward_pool.update_counter = math::add(ctx.accounts.reward_pool.update_counter, 1)?;
+
+        emit!(RewardsCompounded {
+            owner: ctx.accounts.staker_owner.key(),
+            collateral_mint: staker.collateral_mint,
+            reward_weight_added: additional_rewards,
+            reward_weight: staker.staked_amount,
+            slot: current_slot,
+        });
         Ok(())
     }
 
     /// Unstake collateral after the lock period has expired.
+    /// Burns `amount` FLT from the caller's stake escrow and redeems collateral at the current
+    /// exchange rate. The matching share of reward weight, boost and compounding included, is
+    /// removed with it. Not possible while a term loan against the stake is open.
     pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
         let clock = Clock::get()?;
         let current_slot = clock.slot;
-        let staker = &mut ctx.accounts.staker;
-        require!(current_slot >= staker.lock_end_slot, CustomError::StakingLocked);
-        require!(staker.staked_amount >= amount, CustomError::InsufficientStakedAmount);
+        ctx.accounts.governance.require_not_paused(PAUSE_UNSTAKE)?;
+        require!(!ctx.accounts.reward_pool.flash_loan_active, CustomError::ReentrancyDetected);
+        require!(amount > 0, CustomError::InsufficientStakedAmount);
+
+        require!(
+            ctx.accounts.collateral_config.status != CollateralStatus::Frozen,
+            CustomError::CollateralFrozen
+        );
+
+        // Price the redemption before the FLT supply changes.
+        let collateral_amount = math::assets_for_shares(
+            amount,
+            vault_assets(&ctx.accounts.vault_token_account, &ctx.accounts.reward_pool)?,
+            ctx.accounts.flt_mint.supply,
+        )?;
 
-        staker.staked_amount = staker.staked_amount.checked_sub(amount).unwrap();
-        ctx.accounts.reward_pool.total_staked = ctx.accounts.reward_pool.total_staked.checked_sub(amount).unwrap();
-        ctx.accounts.reward_pool.update_counter = ctx.accounts.reward_pool.update_counter.checked_add(1).unwrap();
+        // Release the escrowed FLT and its reward weight, settling fees earned so far.
+        let acc_fee_per_share = ctx.accounts.reward_pool.acc_fee_per_share;
+        let staker = &mut ctx.accounts.staker;
+        let weight_removed = staker.release_escrow(amount, acc_fee_per_share, current_slot)?;
+        ctx.accounts.reward_pool.total_staked = math::sub(ctx.accounts.reward_pool.total_staked, weight_removed)?;
+        ctx.accounts.reward_pool.update_counter = math::add(ctx.accounts.reward_pool.update_counter, 1)?;
 
+        // Burn the FLT being redeemed; the vault PDA is the escrow's authority.
         let seeds = &[b"vault", staker.collateral_mint.as_ref(), &[ctx.accounts.vault_account.bump]];
         let signer = &[&seeds[..]];
+        let burn_cpi_accounts = Burn {
+            mint: ctx.accounts.flt_mint.to_account_info(),
+            from: ctx.accounts.stake_escrow.to_account_info(),
+            authority: ctx.accounts.vault_account.to_account_info(),
+        };
+        token::burn(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), burn_cpi_accounts, signer),
+            amount,
+        )?;
+
         let transfer_cpi_accounts = Transfer {
             from: ctx.accounts.vault_token_account.to_account_info(),
             to: ctx.accounts.user_token_account.to_account_info(),
@@ -315,62 +823,859 @@ pub mod flash_liquidity_token {
         };
         token::transfer(
             CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts, signer),
+            collateral_amount,
+        )?;
+
+        emit!(Unstaked {
+            user: ctx.accounts.user.key(),
+            collateral_mint: staker.collateral_mint,
+            flt_burned: amount,
+            collateral_returned: collateral_amount,
+            reward_weight_removed: weight_removed,
+            slot: current_slot,
+        });
+
+        Ok(())
+    }
+
+    /// Move `amount` FLT out of the caller's stake escrow into their own FLT account, under the
+    /// same lock and open-loan checks as `unstake`. The matching share of reward weight is removed;
+    /// the withdrawn FLT can be transferred, used to vote or redeemed with `redeem`.
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>, amount: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let current_slot = clock.slot;
+        ctx.accounts.governance.require_not_paused(PAUSE_UNSTAKE)?;
+        require!(!ctx.accounts.reward_pool.flash_loan_active, CustomError::ReentrancyDetected);
+        require!(amount > 0, CustomError::InsufficientStakedAmount);
+        require!(
+            ctx.accounts.collateral_config.status != CollateralStatus::Frozen,
+            CustomError::CollateralFrozen
+        );
+
+        let acc_fee_per_share = ctx.accounts.reward_pool.acc_fee_per_share;
+        let staker = &mut ctx.accounts.staker;
+        let weight_removed = staker.release_escrow(amount, acc_fee_per_share, current_slot)?;
+        ctx.accounts.reward_pool.total_staked = math::sub(ctx.accounts.reward_pool.total_staked, weight_removed)?;
+        ctx.accounts.reward_pool.update_counter = math::add(ctx.accounts.reward_pool.update_counter, 1)?;
+
+        let seeds = &[b"vault", staker.collateral_mint.as_ref(), &[ctx.accounts.vault_account.bump]];
+        let signer = &[&seeds[..]];
+        let transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.stake_escrow.to_account_info(),
+            to: ctx.accounts.user_flt_token_account.to_account_info(),
+            authority: ctx.accounts.vault_account.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts, signer),
+            amount,
+        )?;
+
+        emit!(StakeWithdrawn {
+            user: ctx.accounts.user.key(),
+            collateral_mint: staker.collateral_mint,
+            flt_amount: amount,
+            reward_weight_removed: weight_removed,
+            slot: current_slot,
+        });
+
+        Ok(())
+    }
+
+    /// Burn `amount` FLT held in the caller's own FLT account and redeem collateral at the
+    /// current exchange rate. FLT outside a stake escrow carries no lock or reward weight, so
+    /// transferred FLT keeps its redemption right.
+    pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        ctx.accounts.governance.require_not_paused(PAUSE_UNSTAKE)?;
+        require!(!ctx.accounts.reward_pool.flash_loan_active, CustomError::ReentrancyDetected);
+        require!(amount > 0, CustomError::InsufficientStakedAmount);
+        require!(
+            ctx.accounts.collateral_config.status != CollateralStatus::Frozen,
+            CustomError::CollateralFrozen
+        );
+
+        // Price the redemption before the FLT supply changes.
+        let collateral_amount = math::assets_for_shares(
+            amount,
+            vault_assets(&ctx.accounts.vault_token_account, &ctx.accounts.reward_pool)?,
+            ctx.accounts.flt_mint.supply,
+        )?;
+
+        let burn_cpi_accounts = Burn {
+            mint: ctx.accounts.flt_mint.to_account_info(),
+            from: ctx.accounts.user_flt_token_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::burn(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_cpi_accounts),
             amount,
         )?;
+        ctx.accounts.reward_pool.update_counter = math::add(ctx.accounts.reward_pool.update_counter, 1)?;
+
+        let collateral_mint_key = ctx.accounts.collateral_mint.key();
+        let seeds = &[b"vault", collateral_mint_key.as_ref(), &[ctx.accounts.vault_account.bump]];
+        let signer = &[&seeds[..]];
+        let transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            to: ctx.accounts.user_token_account.to_account_info(),
+            authority: ctx.accounts.vault_account.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts, signer),
+            collateral_amount,
+        )?;
+
+        emit!(Redeemed {
+            user: ctx.accounts.user.key(),
+            collateral_mint: collateral_mint_key,
+            flt_burned: amount,
+            collateral_returned: collateral_amount,
+            slot: clock.slot,
+        });
+
+        Ok(())
+    }
+
+    /// Claim the caller's share of fees credited to the reward pool.
+    /// Pending rewards are paid out of the vault token account of the staker's collateral.
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        ctx.accounts.governance.require_not_paused(PAUSE_COMPOUND)?;
+        require!(!ctx.accounts.reward_pool.flash_loan_active, CustomError::ReentrancyDetected);
+
+        let staker = &mut ctx.accounts.staker;
+        staker.settle_rewards(ctx.accounts.reward_pool.acc_fee_per_share)?;
+        staker.sync_reward_debt(ctx.accounts.reward_pool.acc_fee_per_share)?;
+        let rewards = staker.pending_rewards;
+        require!(rewards > 0, CustomError::NoRewardsToClaim);
+        staker.pending_rewards = 0;
+
+        ctx.accounts.reward_pool.reserved_rewards = math::sub(ctx.accounts.reward_pool.reserved_rewards, rewards)?;
+        ctx.accounts.reward_pool.update_counter = math::add(ctx.accounts.reward_pool.update_counter, 1)?;
+
+        let seeds = &[b"vault", staker.collateral_mint.as_ref(), &[ctx.accounts.vault_account.bump]];
+        let signer = &[&seeds[..]];
+        let transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            to: ctx.accounts.user_token_account.to_account_info(),
+            authority: ctx.accounts.vault_account.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts, signer),
+            rewards,
+        )?;
 
+        emit!(RewardsClaimed {
+            owner: ctx.accounts.user.key(),
+            collateral_mint: staker.collateral_mint,
+            amount: rewards,
+            slot: Clock::get()?.slot,
+        });
         Ok(())
     }
 
+    /// Return the current exchange rate of vault assets per FLT, scaled by `EXCHANGE_RATE_SCALE`.
+    /// Read-only; intended to be called via simulation (`.view()` in the TS client).
+    pub fn exchange_rate(ctx: Context<ExchangeRate>) -> Result<u64> {
+        let assets = vault_assets(&ctx.accounts.vault_token_account, &ctx.accounts.reward_pool)?;
+        math::assets_for_shares(EXCHANGE_RATE_SCALE, assets, ctx.accounts.flt_mint.supply)
+    }
+
     /// Update governance parameters.
-    pub fn update_governance_parameters(
-        ctx: Context<UpdateGovernanceParameters>,
-        flash_loan_fee_bps: u64,         // default fee (unused in dynamic mode)
-        liquidation_penalty_bps: u64,
-        liquidation_grace_slots: u64,
-        compound_rate_numerator: u64,
-        compound_rate_denominator: u64,
-        max_borrow_ratio: u64,
-    ) -> Result<()> {
+    /// Only available while the timelock delay is zero; otherwise use `queue_parameter_change`.
+    pub fn update_governance_parameters(ctx: Context<UpdateGovernanceParameters>, params: GovernanceParams) -> Result<()> {
         let governance = &mut ctx.accounts.governance;
-        governance.flash_loan_fee_bps = flash_loan_fee_bps;
-        governance.liquidation_penalty_bps = liquidation_penalty_bps;
-        governance.liquidation_grace_slots = liquidation_grace_slots;
-        governance.compound_rate_numerator = compound_rate_numerator;
-        governance.compound_rate_denominator = compound_rate_denominator;
-        governance.max_borrow_ratio = max_borrow_ratio;
-        Ok(())
+        require!(governance.timelock_delay_slots == 0, CustomError::TimelockActive);
+        params.validate()?;
+        governance.apply(params);
+        emit_governance_updated(governance, ctx.accounts.admin.key())
     }
-}
 
-//
-// Account Contexts
-//
+    /// Set the stakers' and the insurance fund's shares of fees. Fee manager only.
+    /// Only available while the timelock delay is zero; otherwise use `queue_parameter_change`.
+    pub fn set_fee_parameters(
+        ctx: Context<SetFeeParameters>,
+        reward_share_bps: u64,
+        insurance_share_bps: u64,
+    ) -> Result<()> {
+        require!(ctx.accounts.governance.timelock_delay_slots == 0, CustomError::TimelockActive);
+        apply_parameter_change(
+            ParameterChange::FeeParameters { reward_share_bps, insurance_share_bps },
+            ctx.accounts.fee_manager.key(),
+            &mut ctx.accounts.governance,
+            None,
+        )
+    }
 
-#[derive(Accounts)]
-pub struct Stake<'info> {
-    /// The user staking tokens.
-    #[account(mut)]
+    /// Set the liquidation grace period. Risk manager only.
+    /// Only available while the timelock delay is zero; otherwise use `queue_parameter_change`.
+    pub fn set_liquidation_grace_slots(ctx: Context<SetLiquidationGraceSlots>, liquidation_grace_slots: u64) -> Result<()> {
+        require!(ctx.accounts.governance.timelock_delay_slots == 0, CustomError::TimelockActive);
+        apply_parameter_change(
+            ParameterChange::LiquidationGraceSlots(liquidation_grace_slots),
+            ctx.accounts.risk_manager.key(),
+            &mut ctx.accounts.governance,
+            None,
+        )
+    }
+
+    /// Push a manual price for a collateral, used as its secondary oracle. Risk manager only.
+    /// `price` is normalized to `oracle::PRICE_DECIMALS` decimals and timestamped with the current clock.
+    pub fn set_manual_price(ctx: Context<SetManualPrice>, price: u64) -> Result<()> {
+        require!(price > 0, CustomError::OraclePriceUnavailable);
+        let manual_price = &mut ctx.accounts.manual_price;
+        manual_price.collateral_mint = ctx.accounts.collateral_config.collateral_mint;
+        manual_price.price = price;
+        manual_price.publish_time = Clock::get()?.unix_timestamp;
+        manual_price.bump = ctx.bumps.manual_price;
+
+        emit!(ManualPriceSet {
+            authority: ctx.accounts.risk_manager.key(),
+            collateral_mint: manual_price.collateral_mint,
+            price,
+            publish_time: manual_price.publish_time,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    /// Assign the fee, risk, listing and guardian roles. Admin only.
+    pub fn set_roles(ctx: Context<SetRoles>, roles: GovernanceRoles) -> Result<()> {
+        ctx.accounts.governance.roles = roles;
+        emit_governance_updated(&ctx.accounts.governance, ctx.accounts.admin.key())
+    }
+
+    /// Pause the given instruction families (`PAUSE_*` flags). Guardian or admin.
+    /// Pausing repay requires the admin, so borrowers can close positions during a guardian pause.
+    pub fn pause(ctx: Context<SetPause>, flags: u8) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        require!(
+            flags & PAUSE_REPAY == 0 || ctx.accounts.authority.key() == governance.admin,
+            CustomError::Unauthorized
+        );
+        governance.paused |= flags;
+        emit_governance_updated(governance, ctx.accounts.authority.key())
+    }
+
+    /// Resume the given instruction families. Admin only.
+    pub fn unpause(ctx: Context<SetPause>, flags: u8) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        require_keys_eq!(ctx.accounts.authority.key(), governance.admin, CustomError::Unauthorized);
+        governance.paused &= !flags;
+        emit_governance_updated(governance, ctx.accounts.authority.key())
+    }
+
+    /// Nominate a new admin. The transfer completes when the nominee calls `accept_admin`.
+    /// Nominating `Pubkey::default()` cancels a pending transfer.
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.governance.pending_admin = new_admin;
+        Ok(())
+    }
+
+    /// Accept a pending admin nomination.
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        governance.admin = ctx.accounts.pending_admin.key();
+        governance.pending_admin = Pubkey::default();
+        emit_governance_updated(governance, ctx.accounts.pending_admin.key())
+    }
+
+    /// Queue a governance or collateral parameter change.
+    /// Only the role that owns the change (see `ParameterChange::authority`) or the admin can
+    /// queue it. The change becomes executable once `timelock_delay_slots` have passed.
+    pub fn queue_parameter_change(ctx: Context<QueueParameterChange>, change: ParameterChange) -> Result<()> {
+        let clock = Clock::get()?;
+        let governance = &mut ctx.accounts.governance;
+        governance.require_change_authority(&change, ctx.accounts.authority.key())?;
+
+        let pending_change = &mut ctx.accounts.pending_change;
+        pending_change.id = governance.next_change_id;
+        pending_change.change = change;
+        pending_change.eta_slot = math::add(clock.slot, governance.timelock_delay_slots)?;
+        pending_change.bump = ctx.bumps.pending_change;
+
+        governance.next_change_id = math::add(governance.next_change_id, 1)?;
+
+        Ok(())
+    }
+
+    /// Apply a queued parameter change after its ETA and close the pending record.
+    /// Owning role or admin, as for `queue_parameter_change`.
+    pub fn execute_parameter_change(ctx: Context<ExecuteParameterChange>) -> Result<()> {
+        let clock = Clock::get()?;
+        let pending_change = &ctx.accounts.pending_change;
+        ctx.accounts
+            .governance
+            .require_change_authority(&pending_change.change, ctx.accounts.authority.key())?;
+        require!(clock.slot >= pending_change.eta_slot, CustomError::TimelockNotElapsed);
+
+        apply_parameter_change(
+            pending_change.change,
+            ctx.accounts.authority.key(),
+            &mut ctx.accounts.governance,
+            ctx.accounts.collateral_config.as_deref_mut().map(|config| &mut **config),
+        )
+    }
+
+    /// Cancel a queued parameter change and close the pending record.
+    /// Owning role or admin, as for `queue_parameter_change`.
+    pub fn cancel_parameter_change(ctx: Context<CancelParameterChange>) -> Result<()> {
+        ctx.accounts
+            .governance
+            .require_change_authority(&ctx.accounts.pending_change.change, ctx.accounts.authority.key())
+    }
+
+    /// Lock FLT of the voting collateral into the caller's vote escrow.
+    /// Deposits only count toward proposals created in a later slot.
+    pub fn deposit_votes(ctx: Context<DepositVotes>, amount: u64) -> Result<()> {
+        let clock = Clock::get()?;
+
+        let transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.voter_flt_account.to_account_info(),
+            to: ctx.accounts.vote_escrow.to_account_info(),
+            authority: ctx.accounts.voter.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts),
+            amount,
+        )?;
+
+        let voter_record = &mut ctx.accounts.voter_record;
+        voter_record.owner = ctx.accounts.voter.key();
+        voter_record.amount = math::add(voter_record.amount, amount)?;
+        voter_record.last_deposit_slot = clock.slot;
+        voter_record.bump = ctx.bumps.voter_record;
+
+        Ok(())
+    }
+
+    /// Withdraw FLT from the caller's vote escrow once every proposal they voted on has ended.
+    pub fn withdraw_votes(ctx: Context<WithdrawVotes>, amount: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let voter_record = &mut ctx.accounts.voter_record;
+        require!(clock.slot >= voter_record.unlock_slot, CustomError::VotesLocked);
+        require!(voter_record.amount >= amount, CustomError::InsufficientVotingPower);
+        voter_record.amount = math::sub(voter_record.amount, amount)?;
+
+        let seeds = &[b"voter", voter_record.owner.as_ref(), &[voter_record.bump]];
+        let signer = &[&seeds[..]];
+        let transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.vote_escrow.to_account_info(),
+            to: ctx.accounts.voter_flt_account.to_account_info(),
+            authority: voter_record.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_cpi_accounts, signer),
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Open a proposal to apply a parameter change by FLT vote.
+    /// Snapshots the voting FLT supply; only FLT escrowed before this slot can vote.
+    pub fn create_proposal(ctx: Context<CreateProposal>, change: ParameterChange) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(ctx.accounts.voter_record.amount > 0, CustomError::InsufficientVotingPower);
+
+        let governance = &mut ctx.accounts.governance;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.id = governance.next_proposal_id;
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.change = change;
+        proposal.snapshot_slot = clock.slot;
+        proposal.snapshot_supply = ctx.accounts.flt_mint.supply;
+        proposal.voting_end_slot = math::add(clock.slot, governance.voting_period_slots)?;
+        proposal.yes_votes = 0;
+        proposal.no_votes = 0;
+        proposal.executed = false;
+        proposal.bump = ctx.bumps.proposal;
+
+        governance.next_proposal_id = math::add(governance.next_proposal_id, 1)?;
+
+        Ok(())
+    }
+
+    /// Vote for or against a proposal with the caller's escrowed FLT.
+    /// The escrow stays locked until the proposal's voting period ends.
+    pub fn cast_vote(ctx: Context<CastVote>, approve: bool) -> Result<()> {
+        let clock = Clock::get()?;
+        let proposal = &mut ctx.accounts.proposal;
+        let voter_record = &mut ctx.accounts.voter_record;
+        require!(clock.slot < proposal.voting_end_slot, CustomError::VotingClosed);
+        require!(voter_record.last_deposit_slot < proposal.snapshot_slot, CustomError::InsufficientVotingPower);
+        require!(voter_record.amount > 0, CustomError::InsufficientVotingPower);
+
+        let weight = voter_record.amount;
+        if approve {
+            proposal.yes_votes = math::add(proposal.yes_votes, weight)?;
+        } else {
+            proposal.no_votes = math::add(proposal.no_votes, weight)?;
+        }
+        voter_record.unlock_slot = voter_record.unlock_slot.max(proposal.voting_end_slot);
+
+        let vote_record = &mut ctx.accounts.vote_record;
+        vote_record.proposal = proposal.key();
+        vote_record.voter = ctx.accounts.voter.key();
+        vote_record.weight = weight;
+        vote_record.approve = approve;
+        vote_record.bump = ctx.bumps.vote_record;
+
+        Ok(())
+    }
+
+    /// Apply a proposal that met quorum and approval once voting has ended.
+    /// Permissionless; uses the same code path as `update_governance_parameters`.
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        let clock = Clock::get()?;
+        let governance = &ctx.accounts.governance;
+        let proposal = &mut ctx.accounts.proposal;
+        require!(clock.slot >= proposal.voting_end_slot, CustomError::VotingOpen);
+        require!(!proposal.executed, CustomError::ProposalAlreadyExecuted);
+
+        // Quorum and approval thresholds round up, so a vote exactly at the threshold passes.
+        let total_votes = math::add(proposal.yes_votes, proposal.no_votes)?;
+        require!(
+            total_votes > 0
+                && total_votes
+                    >= math::mul_div_ceil(proposal.snapshot_supply, governance.quorum_bps, math::BPS_DENOMINATOR)?,
+            CustomError::QuorumNotReached
+        );
+        require!(
+            proposal.yes_votes >= math::mul_div_ceil(total_votes, governance.approval_bps, math::BPS_DENOMINATOR)?,
+            CustomError::ProposalRejected
+        );
+        proposal.executed = true;
+
+        apply_parameter_change(
+            proposal.change,
+            proposal.key(),
+            &mut ctx.accounts.governance,
+            ctx.accounts.collateral_config.as_deref_mut().map(|config| &mut **config),
+        )
+    }
+}
+
+//
+// Helpers
+//
+
+/// Apply a direct, timelocked or voted parameter change on behalf of `authority`.
+/// Collateral changes require the matching `CollateralConfig`.
+fn apply_parameter_change(
+    change: ParameterChange,
+    authority: Pubkey,
+    governance: &mut Governance,
+    collateral_config: Option<&mut CollateralConfig>,
+) -> Result<()> {
+    let params = match change {
+        ParameterChange::Governance(params) => params,
+        ParameterChange::FeeParameters { reward_share_bps, insurance_share_bps } => {
+            GovernanceParams { reward_share_bps, insurance_share_bps, ..governance.params() }
+        }
+        ParameterChange::LiquidationGraceSlots(liquidation_grace_slots) => {
+            GovernanceParams { liquidation_grace_slots, ..governance.params() }
+        }
+        _ => {
+            let collateral_config = collateral_config.ok_or(CustomError::CollateralConfigMismatch)?;
+            return apply_collateral_change(change, authority, collateral_config);
+        }
+    };
+    params.validate()?;
+    governance.apply(params);
+    emit_governance_updated(governance, authority)
+}
+
+/// Apply a collateral parameter change to its `CollateralConfig` and emit `CollateralUpdated`.
+fn apply_collateral_change(
+    change: ParameterChange,
+    authority: Pubkey,
+    collateral_config: &mut CollateralConfig,
+) -> Result<()> {
+    let collateral_mint = change.collateral_mint().ok_or(CustomError::CollateralConfigMismatch)?;
+    require_keys_eq!(collateral_config.collateral_mint, collateral_mint, CustomError::CollateralConfigMismatch);
+    match change {
+        ParameterChange::Collateral { params, status, .. } => {
+            params.fee_curve.validate()?;
+            collateral_config.apply(params);
+            collateral_config.status = status;
+        }
+        ParameterChange::CollateralFeeCurve { fee_curve, .. } => {
+            fee_curve.validate()?;
+            collateral_config.fee_curve = fee_curve;
+        }
+        ParameterChange::CollateralRisk { params, .. } => collateral_config.apply_risk(params),
+        ParameterChange::ListingStatus { status, .. } => collateral_config.status = status,
+        ParameterChange::Governance(_)
+        | ParameterChange::FeeParameters { .. }
+        | ParameterChange::LiquidationGraceSlots(_) => {}
+    }
+    emit!(CollateralUpdated {
+        authority,
+        collateral_mint,
+        status: collateral_config.status,
+        params: collateral_config.params(),
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Emit `GovernanceUpdated` with the governance account's current settings.
+fn emit_governance_updated(governance: &Governance, authority: Pubkey) -> Result<()> {
+    emit!(GovernanceUpdated {
+        authority,
+        admin: governance.admin,
+        roles: governance.roles,
+        params: governance.params(),
+        paused: governance.paused,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+/// Fixed-point scale of the rate returned by `exchange_rate` (1.0 == 1_000_000_000).
+pub const EXCHANGE_RATE_SCALE: u64 = 1_000_000_000;
+
+/// Total collateral backing FLT: tokens on hand plus principal lent out,
+/// less fees already reserved for stakers to claim. The insurance fund is held in its own account.
+fn vault_assets(vault_token_account: &TokenAccount, reward_pool: &RewardPool) -> Result<u64> {
+    math::vault_assets(vault_token_account.amount, reward_pool.active_loan_total, reward_pool.reserved_rewards)
+}
+
+/// Credit a collected fee to the reward pool.
+/// `insurance_share_bps` of the fee is set aside for the insurance fund and `reward_share_bps`
+/// is reserved for stakers via `acc_fee_per_share`; the rest stays in the vault and raises the
+/// FLT exchange rate. With no reward weight staked, the stakers' share also goes to the exchange rate.
+/// Returns the insurance share, which the caller moves from the vault to the insurance fund's
+/// token account.
+fn credit_fee(reward_pool: &mut RewardPool, fee: u64, governance: &Governance) -> Result<u64> {
+    reward_pool.accrued_fees = math::add(reward_pool.accrued_fees, fee)?;
+    let insurance = math::insurance_share(fee, governance.insurance_share_bps)?;
+    reward_pool.insurance_fund = math::add(reward_pool.insurance_fund, insurance)?;
+    if reward_pool.total_staked == 0 {
+        return Ok(insurance);
+    }
+    let reward = math::reward_share(fee, governance.reward_share_bps)?;
+    reward_pool.acc_fee_per_share = reward_pool
+        .acc_fee_per_share
+        .checked_add(math::acc_fee_per_share_increment(reward, reward_pool.total_staked)?)
+        .ok_or(CustomError::MathOverflow)?;
+    reward_pool.reserved_rewards = math::add(reward_pool.reserved_rewards, reward)?;
+    Ok(insurance)
+}
+
+/// Utilization of the vault's assets in basis points after borrowing `amount`, capped at 100%.
+/// Priced before the borrowed funds leave the vault. Reward weight plays no part.
+fn post_borrow_utilization_bps(
+    vault_token_account: &TokenAccount,
+    reward_pool: &RewardPool,
+    amount: u64,
+) -> Result<u64> {
+    math::utilization_bps(
+        math::add(reward_pool.active_loan_total, amount)?,
+        vault_assets(vault_token_account, reward_pool)?,
+    )
+}
+
+/// Compute the flash loan fee (in basis points) at `utilization_bps`.
+/// The curve fee is adjusted by the normalized collateral price (see `oracle::adjust_fee_bps`).
+fn dynamic_fee_bps(collateral_config: &CollateralConfig, utilization_bps: u64, price: u64) -> Result<u64> {
+    let flash_fee_bps = collateral_config.fee_curve.fee_bps(utilization_bps)?;
+
+    // Oracle Integration: scale the curve fee by the normalized price.
+    oracle::adjust_fee_bps(flash_fee_bps, price, collateral_config.fee_curve.reference_price)
+}
+
+/// Normalized price of a collateral from its primary Pyth feed, falling back to the
+/// configured secondary source when the primary is stale (see `oracle::resolve_price`).
+/// `pyth_price` must already be checked against `collateral_config.pyth_feed`. A configured
+/// secondary source must be passed, so callers cannot skip the deviation check by omitting it.
+fn collateral_price(
+    collateral_config: &CollateralConfig,
+    pyth_price: &AccountInfo,
+    secondary_price: Option<&AccountInfo>,
+    current_time: u64,
+) -> Result<u64> {
+    let max_age = collateral_config.max_price_age_secs;
+    let primary = oracle::load_normalized_price(pyth_price, max_age, current_time);
+    let secondary = match (collateral_config.secondary_oracle, secondary_price) {
+        (SecondaryOracle::None, _) => None,
+        (_, None) => return err!(CustomError::SecondaryOracleMissing),
+        (SecondaryOracle::Pyth(feed), Some(account)) => {
+            require_keys_eq!(account.key(), feed, CustomError::OracleFeedMismatch);
+            Some(oracle::load_normalized_price(account, max_age, current_time))
+        }
+        (SecondaryOracle::Manual, Some(account)) => {
+            require_keys_eq!(*account.owner, crate::ID, CustomError::OracleFeedMismatch);
+            let manual_price = ManualPrice::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+            require_keys_eq!(
+                manual_price.collateral_mint,
+                collateral_config.collateral_mint,
+                CustomError::OracleFeedMismatch
+            );
+            Some(manual_price.normalized_price(max_age, current_time))
+        }
+    };
+    oracle::resolve_price(primary, secondary, collateral_config.max_deviation_bps)
+}
+
+/// Account index of the loan in the `Repay` instruction.
+const REPAY_LOAN_INDEX: usize = 4;
+/// Account index of the vault token account in the `Repay` instruction.
+const REPAY_VAULT_TOKEN_INDEX: usize = 3;
+
+/// Scan the instructions sysvar for a `repay` of this program, later in the transaction,
+/// that closes the given loan against the same vault with at least `amount`.
+fn require_flash_repay(
+    instructions_sysvar: &AccountInfo,
+    loan: Pubkey,
+    vault_token_account: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let current_index = ix_sysvar::load_current_index_checked(instructions_sysvar)? as usize;
+
+    // The borrow must be a top-level instruction, otherwise a wrapping program could
+    // supply the repay on our behalf from a different context.
+    let current_ix = ix_sysvar::load_instruction_at_checked(current_index, instructions_sysvar)?;
+    require_keys_eq!(current_ix.program_id, crate::ID, CustomError::FlashLoanCpiForbidden);
+
+    let mut index = current_index.checked_add(1).ok_or(CustomError::MathOverflow)?;
+    while let Ok(ix) = ix_sysvar::load_instruction_at_checked(index, instructions_sysvar) {
+        let repay_amount = ix.data.get(8..16).and_then(|bytes| bytes.try_into().ok()).map(u64::from_le_bytes);
+        if ix.program_id == crate::ID
+            && ix.data.get(..8) == Some(&crate::instruction::Repay::DISCRIMINATOR[..])
+            && ix.accounts.get(REPAY_LOAN_INDEX).map(|meta| meta.pubkey) == Some(loan)
+            && ix.accounts.get(REPAY_VAULT_TOKEN_INDEX).map(|meta| meta.pubkey) == Some(vault_token_account)
+            && repay_amount.is_some_and(|repay_amount| repay_amount >= amount)
+        {
+            return Ok(());
+        }
+        index = index.checked_add(1).ok_or(CustomError::MathOverflow)?;
+    }
+
+    err!(CustomError::FlashRepayMissing)
+}
+
+//
+// Account Contexts
+//
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    /// The protocol admin paying for account creation.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// The governance PDA, derived as: seeds = [b"governance"].
+    #[account(
+        init,
+        payer = admin,
+        space = Governance::LEN,
+        seeds = [b"governance"],
+        bump
+    )]
+    pub governance: Box<Account<'info, Governance>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ListCollateral<'info> {
+    /// Only the listing manager can list collaterals.
+    #[account(mut, address = governance.roles.listing_manager)]
+    pub listing_manager: Signer<'info>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The collateral mint being listed.
+    pub collateral_mint: Box<Account<'info, Mint>>,
+    /// The collateral's risk configuration, derived as: seeds = [b"collateral_config", collateral_mint.key().as_ref()].
+    #[account(
+        init,
+        payer = listing_manager,
+        space = CollateralConfig::LEN,
+        seeds = [b"collateral_config", collateral_mint.key().as_ref()],
+        bump
+    )]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+    /// The vault PDA account, derived as: seeds = [b"vault", collateral_mint.key().as_ref()].
+    #[account(
+        init_if_needed,
+        payer = listing_manager,
+        space = VaultAccount::LEN,
+        seeds = [b"vault", collateral_mint.key().as_ref()],
+        bump
+    )]
+    pub vault_account: Box<Account<'info, VaultAccount>>,
+    /// The vault token account holding collateral, owned by the vault PDA.
+    #[account(
+        init_if_needed,
+        payer = listing_manager,
+        seeds = [b"vault_token", collateral_mint.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = vault_account
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    /// The insurance fund's token account, owned by the vault PDA and kept apart from the
+    /// collateral backing FLT.
+    #[account(
+        init_if_needed,
+        payer = listing_manager,
+        seeds = [b"insurance", collateral_mint.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = vault_account
+    )]
+    pub insurance_token_account: Box<Account<'info, TokenAccount>>,
+    /// The FLT mint for this collateral; the mint PDA is its own authority.
+    #[account(
+        init_if_needed,
+        payer = listing_manager,
+        seeds = [b"flt_mint", collateral_mint.key().as_ref()],
+        bump,
+        mint::decimals = collateral_mint.decimals,
+        mint::authority = flt_mint
+    )]
+    pub flt_mint: Box<Account<'info, Mint>>,
+    /// Helper account storing the bump for the FLT mint PDA.
+    #[account(
+        init_if_needed,
+        payer = listing_manager,
+        space = MintWrapper::LEN,
+        seeds = [b"flt_mint_wrapper", collateral_mint.key().as_ref()],
+        bump
+    )]
+    pub flt_mint_wrapper: Account<'info, MintWrapper>,
+    /// The reward pool PDA, derived as: seeds = [b"reward_pool", collateral_mint.key().as_ref()].
+    #[account(
+        init_if_needed,
+        payer = listing_manager,
+        space = RewardPool::LEN,
+        seeds = [b"reward_pool", collateral_mint.key().as_ref()],
+        bump
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCollateral<'info> {
+    /// Only the listing manager can change a collateral's listing status.
+    #[account(address = governance.roles.listing_manager)]
+    pub listing_manager: Signer<'info>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The collateral's risk configuration.
+    #[account(
+        mut,
+        seeds = [b"collateral_config", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct SetCollateralFeeCurve<'info> {
+    /// Only the fee manager can change fee curves.
+    #[account(address = governance.roles.fee_manager)]
+    pub fee_manager: Signer<'info>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The collateral's risk configuration.
+    #[account(
+        mut,
+        seeds = [b"collateral_config", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct SetCollateralRisk<'info> {
+    /// Only the risk manager can change ratios, penalties and caps.
+    #[account(address = governance.roles.risk_manager)]
+    pub risk_manager: Signer<'info>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The collateral's risk configuration.
+    #[account(
+        mut,
+        seeds = [b"collateral_config", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct DelistCollateral<'info> {
+    /// Only the listing manager can delist collaterals.
+    #[account(mut, address = governance.roles.listing_manager)]
+    pub listing_manager: Signer<'info>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The collateral mint being delisted.
+    pub collateral_mint: Box<Account<'info, Mint>>,
+    /// The collateral's risk configuration, closed to the listing manager.
+    #[account(
+        mut,
+        close = listing_manager,
+        seeds = [b"collateral_config", collateral_mint.key().as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+    /// The FLT mint corresponding to this collateral.
+    #[account(seeds = [b"flt_mint", collateral_mint.key().as_ref()], bump)]
+    pub flt_mint: Box<Account<'info, Mint>>,
+    /// The reward pool for this collateral.
+    #[account(seeds = [b"reward_pool", collateral_mint.key().as_ref()], bump = reward_pool.bump)]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    /// The user staking tokens.
+    #[account(mut)]
     pub user: Signer<'info>,
     /// The user's token account holding collateral.
     #[account(mut)]
     pub user_token_account: Box<Account<'info, TokenAccount>>,
     /// The vault token account (PDA) where collateral is stored.
-    #[account(mut)]
+    #[account(mut, seeds = [b"vault_token", collateral_mint.key().as_ref()], bump)]
     pub vault_token_account: Box<Account<'info, TokenAccount>>,
     /// The collateral mint.
     pub collateral_mint: Box<Account<'info, Mint>>,
     /// The FLT mint corresponding to this collateral.
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"flt_mint", collateral_mint.key().as_ref()],
+        bump = flt_mint_wrapper.bump
+    )]
     pub flt_mint: Box<Account<'info, Mint>>,
     /// Helper account storing the bump for the FLT mint PDA.
+    #[account(seeds = [b"flt_mint_wrapper", collateral_mint.key().as_ref()], bump)]
     pub flt_mint_wrapper: Account<'info, MintWrapper>,
-    /// The user's token account to receive minted FLT tokens.
-    #[account(mut)]
-    pub user_flt_token_account: Box<Account<'info, TokenAccount>>,
+    /// The staker's FLT escrow receiving the minted FLT, owned by the vault PDA.
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"stake_escrow", user.key().as_ref(), collateral_mint.key().as_ref()],
+        bump,
+        token::mint = flt_mint,
+        token::authority = vault_account
+    )]
+    pub stake_escrow: Box<Account<'info, TokenAccount>>,
     /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
     pub governance: Box<Account<'info, Governance>>,
-    /// Global reward pool account.
-    #[account(mut)]
+    /// The risk configuration for this collateral.
+    #[account(seeds = [b"collateral_config", collateral_mint.key().as_ref()], bump = collateral_config.bump)]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+    /// The reward pool for this collateral.
+    #[account(mut, seeds = [b"reward_pool", collateral_mint.key().as_ref()], bump = reward_pool.bump)]
     pub reward_pool: Box<Account<'info, RewardPool>>,
     /// Staker record (tracked per user per collateral type).
     #[account(
@@ -385,7 +1690,7 @@ pub struct Stake<'info> {
     #[account(
         mut,
         seeds = [b"vault", collateral_mint.key().as_ref()],
-        bump
+        bump = vault_account.bump
     )]
     pub vault_account: Box<Account<'info, VaultAccount>>,
     pub token_program: Program<'info, Token>,
@@ -405,11 +1710,11 @@ pub struct Borrow<'info> {
     #[account(
         mut,
         seeds = [b"vault", staker.collateral_mint.as_ref()],
-        bump
+        bump = vault_account.bump
     )]
     pub vault_account: Box<Account<'info, VaultAccount>>,
     /// The vault token account from which liquidity is drawn.
-    #[account(mut)]
+    #[account(mut, seeds = [b"vault_token", staker.collateral_mint.as_ref()], bump)]
     pub vault_token_account: Box<Account<'info, TokenAccount>>,
     /// The staker record for collateralized borrowing.
     #[account(
@@ -418,21 +1723,98 @@ pub struct Borrow<'info> {
         bump
     )]
     pub staker: Box<Account<'info, Staker>>,
-    /// A new loan record.
-    #[account(init, payer = borrower, space = Loan::LEN)]
+    /// The borrower's loan counters, derived as: seeds = [b"borrower", borrower.key().as_ref()].
+    #[account(
+        init_if_needed,
+        payer = borrower,
+        space = BorrowerState::LEN,
+        seeds = [b"borrower", borrower.key().as_ref()],
+        bump
+    )]
+    pub borrower_state: Box<Account<'info, BorrowerState>>,
+    /// A new loan record, derived from the borrower, collateral mint and the borrower's next nonce.
+    #[account(
+        init,
+        payer = borrower,
+        space = Loan::LEN,
+        seeds = [
+            b"loan",
+            borrower.key().as_ref(),
+            staker.collateral_mint.as_ref(),
+            &borrower_state.next_loan_nonce.to_le_bytes()
+        ],
+        bump
+    )]
     pub loan: Box<Account<'info, Loan>>,
     /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
     pub governance: Box<Account<'info, Governance>>,
-    /// Global reward pool account.
+    /// The risk configuration for this collateral.
+    #[account(seeds = [b"collateral_config", staker.collateral_mint.as_ref()], bump = collateral_config.bump)]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+    /// The reward pool for this collateral.
+    #[account(mut, seeds = [b"reward_pool", staker.collateral_mint.as_ref()], bump = reward_pool.bump)]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+    /// The FLT mint of this collateral; its supply prices the escrowed FLT backing term loans.
+    #[account(seeds = [b"flt_mint", staker.collateral_mint.as_ref()], bump)]
+    pub flt_mint: Box<Account<'info, Mint>>,
+    /// The Pyth oracle price account bound to this collateral.
+    #[account(address = collateral_config.pyth_feed @ CustomError::OracleFeedMismatch)]
+    pub pyth_price: AccountInfo<'info>,
+    /// The collateral's secondary price source, if configured; used when the primary is stale.
+    pub secondary_price: Option<AccountInfo<'info>>,
+    /// The instructions sysvar, used to find the matching repay in flash mode.
+    #[account(address = ix_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    /// The insurance fund's token account, owned by the vault PDA.
+    #[account(mut, seeds = [b"insurance", staker.collateral_mint.as_ref()], bump)]
+    pub insurance_token_account: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    /// The borrower.
     #[account(mut)]
+    pub borrower: Signer<'info>,
+    /// The borrower's token account to receive liquidity.
+    #[account(mut, token::mint = collateral_mint)]
+    pub borrower_token_account: Box<Account<'info, TokenAccount>>,
+    /// The collateral mint being borrowed.
+    pub collateral_mint: Box<Account<'info, Mint>>,
+    /// The vault PDA account.
+    #[account(
+        mut,
+        seeds = [b"vault", collateral_mint.key().as_ref()],
+        bump = vault_account.bump
+    )]
+    pub vault_account: Box<Account<'info, VaultAccount>>,
+    /// The vault token account from which liquidity is drawn and to which it must be returned.
+    #[account(mut, seeds = [b"vault_token", collateral_mint.key().as_ref()], bump)]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The risk configuration for this collateral.
+    #[account(seeds = [b"collateral_config", collateral_mint.key().as_ref()], bump = collateral_config.bump)]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+    /// The reward pool for this collateral.
+    #[account(mut, seeds = [b"reward_pool", collateral_mint.key().as_ref()], bump = reward_pool.bump)]
     pub reward_pool: Box<Account<'info, RewardPool>>,
     /// The callback program to be invoked after funds transfer.
+    #[account(executable)]
     pub callback_program: AccountInfo<'info>,
-    /// The Pyth oracle price account.
+    /// The Pyth oracle price account bound to this collateral.
+    #[account(address = collateral_config.pyth_feed @ CustomError::OracleFeedMismatch)]
     pub pyth_price: AccountInfo<'info>,
+    /// The collateral's secondary price source, if configured; used when the primary is stale.
+    pub secondary_price: Option<AccountInfo<'info>>,
     pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+    /// The insurance fund's token account, owned by the vault PDA.
+    #[account(mut, seeds = [b"insurance", collateral_mint.key().as_ref()], bump)]
+    pub insurance_token_account: Box<Account<'info, TokenAccount>>,
 }
 
 #[derive(Accounts)]
@@ -446,24 +1828,81 @@ pub struct Repay<'info> {
     /// The vault PDA account.
     #[account(
         mut,
-        seeds = [b"vault", staker.collateral_mint.as_ref()],
-        bump
+        seeds = [b"vault", loan.collateral_mint.as_ref()],
+        bump = vault_account.bump
     )]
     pub vault_account: Box<Account<'info, VaultAccount>>,
     /// The vault token account to receive the repayment.
-    #[account(mut)]
+    #[account(mut, seeds = [b"vault_token", loan.collateral_mint.as_ref()], bump)]
     pub vault_token_account: Box<Account<'info, TokenAccount>>,
-    /// The loan record being repaid (will be closed on success).
-    #[account(mut, close = borrower)]
+    /// The loan record being repaid (closed once fully repaid).
+    #[account(
+        mut,
+        has_one = borrower,
+        seeds = [b"loan", loan.borrower.as_ref(), loan.collateral_mint.as_ref(), &loan.nonce.to_le_bytes()],
+        bump = loan.bump
+    )]
     pub loan: Box<Account<'info, Loan>>,
-    /// The staker record.
+    /// The borrower's staker record for the loan's collateral; repaid term principal unlocks its escrow.
+    #[account(mut, seeds = [b"staker", loan.borrower.as_ref(), loan.collateral_mint.as_ref()], bump)]
     pub staker: Box<Account<'info, Staker>>,
     /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
     pub governance: Box<Account<'info, Governance>>,
-    /// Global reward pool account.
+    /// The risk configuration for this collateral.
+    #[account(seeds = [b"collateral_config", loan.collateral_mint.as_ref()], bump = collateral_config.bump)]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+    /// The reward pool for this collateral.
+    #[account(mut, seeds = [b"reward_pool", loan.collateral_mint.as_ref()], bump = reward_pool.bump)]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+    /// The borrower's loan counters.
+    #[account(mut, seeds = [b"borrower", loan.borrower.as_ref()], bump = borrower_state.bump)]
+    pub borrower_state: Box<Account<'info, BorrowerState>>,
+    pub token_program: Program<'info, Token>,
+    /// The insurance fund's token account, owned by the vault PDA.
+    #[account(mut, seeds = [b"insurance", loan.collateral_mint.as_ref()], bump)]
+    pub insurance_token_account: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendLoan<'info> {
+    /// The borrower extending the loan.
+    pub borrower: Signer<'info>,
+    /// The borrower's token account (source of the extension fee).
     #[account(mut)]
+    pub borrower_token_account: Box<Account<'info, TokenAccount>>,
+    /// The vault token account receiving the extension fee.
+    #[account(mut, seeds = [b"vault_token", loan.collateral_mint.as_ref()], bump)]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    /// The loan record being extended.
+    #[account(
+        mut,
+        has_one = borrower,
+        seeds = [b"loan", loan.borrower.as_ref(), loan.collateral_mint.as_ref(), &loan.nonce.to_le_bytes()],
+        bump = loan.bump
+    )]
+    pub loan: Box<Account<'info, Loan>>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The risk configuration for this collateral.
+    #[account(seeds = [b"collateral_config", loan.collateral_mint.as_ref()], bump = collateral_config.bump)]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+    /// The reward pool for this collateral.
+    #[account(mut, seeds = [b"reward_pool", loan.collateral_mint.as_ref()], bump = reward_pool.bump)]
     pub reward_pool: Box<Account<'info, RewardPool>>,
+    /// The Pyth oracle price account bound to this collateral.
+    #[account(address = collateral_config.pyth_feed @ CustomError::OracleFeedMismatch)]
+    pub pyth_price: AccountInfo<'info>,
+    /// The collateral's secondary price source, if configured; used when the primary is stale.
+    pub secondary_price: Option<AccountInfo<'info>>,
     pub token_program: Program<'info, Token>,
+    /// The vault PDA account, authority of the insurance fund's token account.
+    #[account(seeds = [b"vault", loan.collateral_mint.as_ref()], bump = vault_account.bump)]
+    pub vault_account: Box<Account<'info, VaultAccount>>,
+    /// The insurance fund's token account, owned by the vault PDA.
+    #[account(mut, seeds = [b"insurance", loan.collateral_mint.as_ref()], bump)]
+    pub insurance_token_account: Box<Account<'info, TokenAccount>>,
 }
 
 #[derive(Accounts)]
@@ -471,155 +1910,1294 @@ pub struct Liquidate<'info> {
     /// The liquidator.
     #[account(mut)]
     pub liquidator: Signer<'info>,
-    /// The liquidator's token account to receive collateral.
-    #[account(mut)]
+    /// The liquidator's token account to receive the bonus collateral.
+    #[account(mut, token::mint = loan.collateral_mint)]
     pub liquidator_token_account: Box<Account<'info, TokenAccount>>,
     /// The vault PDA account.
     #[account(
         mut,
-        seeds = [b"vault", staker.collateral_mint.as_ref()],
-        bump
+        seeds = [b"vault", loan.collateral_mint.as_ref()],
+        bump = vault_account.bump
     )]
     pub vault_account: Box<Account<'info, VaultAccount>>,
     /// The vault token account (holds staked collateral).
-    #[account(mut)]
+    #[account(mut, seeds = [b"vault_token", loan.collateral_mint.as_ref()], bump)]
     pub vault_token_account: Box<Account<'info, TokenAccount>>,
     /// The loan record to be liquidated.
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"loan", loan.borrower.as_ref(), loan.collateral_mint.as_ref(), &loan.nonce.to_le_bytes()],
+        bump = loan.bump
+    )]
     pub loan: Box<Account<'info, Loan>>,
+    /// The borrower's staker record for the loan's collateral, debited on liquidation.
+    #[account(mut, seeds = [b"staker", loan.borrower.as_ref(), loan.collateral_mint.as_ref()], bump)]
+    pub staker: Box<Account<'info, Staker>>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The risk configuration for this collateral.
+    #[account(seeds = [b"collateral_config", loan.collateral_mint.as_ref()], bump = collateral_config.bump)]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+    /// The reward pool for this collateral.
+    #[account(mut, seeds = [b"reward_pool", loan.collateral_mint.as_ref()], bump = reward_pool.bump)]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+    /// The borrower's loan counters.
+    #[account(mut, seeds = [b"borrower", loan.borrower.as_ref()], bump = borrower_state.bump)]
+    pub borrower_state: Box<Account<'info, BorrowerState>>,
+    /// The Pyth oracle price account bound to this collateral.
+    #[account(address = collateral_config.pyth_feed @ CustomError::OracleFeedMismatch)]
+    pub pyth_price: AccountInfo<'info>,
+    /// The collateral's secondary price source, if configured; used when the primary is stale.
+    pub secondary_price: Option<AccountInfo<'info>>,
+    pub token_program: Program<'info, Token>,
+    /// The FLT mint of this collateral; the seized FLT is burned from its supply.
+    #[account(mut, seeds = [b"flt_mint", loan.collateral_mint.as_ref()], bump)]
+    pub flt_mint: Box<Account<'info, Mint>>,
+    /// The borrower's stake escrow, seized from.
+    #[account(
+        mut,
+        seeds = [b"stake_escrow", loan.borrower.as_ref(), loan.collateral_mint.as_ref()],
+        bump,
+        token::mint = flt_mint
+    )]
+    pub stake_escrow: Box<Account<'info, TokenAccount>>,
+    /// The insurance fund's token account, owned by the vault PDA.
+    #[account(mut, seeds = [b"insurance", loan.collateral_mint.as_ref()], bump)]
+    pub insurance_token_account: Box<Account<'info, TokenAccount>>,
+    /// The borrower, who receives the closed loan record's rent.
+    #[account(mut, address = loan.borrower)]
+    pub borrower: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CompoundRewards<'info> {
     /// The staker record.
+    #[account(mut, seeds = [b"staker", staker_owner.key().as_ref(), staker.collateral_mint.as_ref()], bump)]
     pub staker: Box<Account<'info, Staker>>,
+    /// The owner of the staker record.
+    pub staker_owner: Signer<'info>,
     /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
     pub governance: Box<Account<'info, Governance>>,
-    /// Global reward pool account.
+    /// The reward pool for this collateral.
+    #[account(mut, seeds = [b"reward_pool", staker.collateral_mint.as_ref()], bump = reward_pool.bump)]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    /// The user unstaking collateral.
     #[account(mut)]
+    pub user: Signer<'info>,
+    /// The user's token account to receive collateral.
+    #[account(mut, token::mint = collateral_mint)]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    /// The collateral mint being redeemed.
+    pub collateral_mint: Box<Account<'info, Mint>>,
+    /// The FLT mint corresponding to this collateral.
+    #[account(
+        mut,
+        seeds = [b"flt_mint", collateral_mint.key().as_ref()],
+        bump = flt_mint_wrapper.bump
+    )]
+    pub flt_mint: Box<Account<'info, Mint>>,
+    /// Helper account storing the bump for the FLT mint PDA.
+    #[account(seeds = [b"flt_mint_wrapper", collateral_mint.key().as_ref()], bump)]
+    pub flt_mint_wrapper: Account<'info, MintWrapper>,
+    /// The user's stake escrow to burn from.
+    #[account(
+        mut,
+        seeds = [b"stake_escrow", user.key().as_ref(), collateral_mint.key().as_ref()],
+        bump,
+        token::mint = flt_mint
+    )]
+    pub stake_escrow: Box<Account<'info, TokenAccount>>,
+    /// The vault PDA account, authority of the vault token account and the stake escrow.
+    #[account(
+        mut,
+        seeds = [b"vault", collateral_mint.key().as_ref()],
+        bump = vault_account.bump
+    )]
+    pub vault_account: Box<Account<'info, VaultAccount>>,
+    /// The vault token account from which collateral is withdrawn.
+    #[account(mut, seeds = [b"vault_token", collateral_mint.key().as_ref()], bump)]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The risk configuration for this collateral.
+    #[account(seeds = [b"collateral_config", collateral_mint.key().as_ref()], bump = collateral_config.bump)]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+    /// The user's staker record (escrowed FLT, lock state and reward weight).
+    #[account(mut, seeds = [b"staker", user.key().as_ref(), collateral_mint.key().as_ref()], bump)]
+    pub staker: Box<Account<'info, Staker>>,
+    /// The reward pool for this collateral.
+    #[account(mut, seeds = [b"reward_pool", collateral_mint.key().as_ref()], bump = reward_pool.bump)]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    /// The staker withdrawing FLT from their escrow.
+    pub user: Signer<'info>,
+    /// The collateral mint of the stake.
+    pub collateral_mint: Box<Account<'info, Mint>>,
+    /// The FLT mint corresponding to this collateral.
+    #[account(seeds = [b"flt_mint", collateral_mint.key().as_ref()], bump)]
+    pub flt_mint: Box<Account<'info, Mint>>,
+    /// The user's stake escrow.
+    #[account(
+        mut,
+        seeds = [b"stake_escrow", user.key().as_ref(), collateral_mint.key().as_ref()],
+        bump,
+        token::mint = flt_mint
+    )]
+    pub stake_escrow: Box<Account<'info, TokenAccount>>,
+    /// The FLT token account receiving the withdrawal.
+    #[account(mut, token::mint = flt_mint)]
+    pub user_flt_token_account: Box<Account<'info, TokenAccount>>,
+    /// The vault PDA account, authority of the stake escrow.
+    #[account(seeds = [b"vault", collateral_mint.key().as_ref()], bump = vault_account.bump)]
+    pub vault_account: Box<Account<'info, VaultAccount>>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The risk configuration for this collateral.
+    #[account(seeds = [b"collateral_config", collateral_mint.key().as_ref()], bump = collateral_config.bump)]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+    /// The user's staker record.
+    #[account(mut, seeds = [b"staker", user.key().as_ref(), collateral_mint.key().as_ref()], bump)]
+    pub staker: Box<Account<'info, Staker>>,
+    /// The reward pool for this collateral.
+    #[account(mut, seeds = [b"reward_pool", collateral_mint.key().as_ref()], bump = reward_pool.bump)]
     pub reward_pool: Box<Account<'info, RewardPool>>,
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct CompoundRewards<'info> {
-    /// The staker record.
-    #[account(mut, seeds = [b"staker", staker_owner.key().as_ref(), staker.collateral_mint.as_ref()], bump)]
-    pub staker: Box<Account<'info, Staker>>,
-    /// The owner of the staker record.
-    pub staker_owner: Signer<'info>,
+pub struct Redeem<'info> {
+    /// The FLT holder redeeming collateral.
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// The user's token account to receive collateral.
+    #[account(mut, token::mint = collateral_mint)]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    /// The collateral mint being redeemed.
+    pub collateral_mint: Box<Account<'info, Mint>>,
+    /// The FLT mint corresponding to this collateral.
+    #[account(mut, seeds = [b"flt_mint", collateral_mint.key().as_ref()], bump)]
+    pub flt_mint: Box<Account<'info, Mint>>,
+    /// The user's FLT token account to burn from.
+    #[account(mut, token::mint = flt_mint, token::authority = user)]
+    pub user_flt_token_account: Box<Account<'info, TokenAccount>>,
+    /// The vault PDA account.
+    #[account(seeds = [b"vault", collateral_mint.key().as_ref()], bump = vault_account.bump)]
+    pub vault_account: Box<Account<'info, VaultAccount>>,
+    /// The vault token account from which collateral is withdrawn.
+    #[account(mut, seeds = [b"vault_token", collateral_mint.key().as_ref()], bump)]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The risk configuration for this collateral.
+    #[account(seeds = [b"collateral_config", collateral_mint.key().as_ref()], bump = collateral_config.bump)]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+    /// The reward pool for this collateral.
+    #[account(mut, seeds = [b"reward_pool", collateral_mint.key().as_ref()], bump = reward_pool.bump)]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    /// The staker claiming rewards.
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// The user's token account to receive the rewards.
+    #[account(mut, token::mint = staker.collateral_mint)]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    /// The vault PDA account.
+    #[account(
+        mut,
+        seeds = [b"vault", staker.collateral_mint.as_ref()],
+        bump = vault_account.bump
+    )]
+    pub vault_account: Box<Account<'info, VaultAccount>>,
+    /// The vault token account from which rewards are paid.
+    #[account(mut, seeds = [b"vault_token", staker.collateral_mint.as_ref()], bump)]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    /// The staker record.
+    #[account(mut, seeds = [b"staker", user.key().as_ref(), staker.collateral_mint.as_ref()], bump)]
+    pub staker: Box<Account<'info, Staker>>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The reward pool for this collateral.
+    #[account(mut, seeds = [b"reward_pool", staker.collateral_mint.as_ref()], bump = reward_pool.bump)]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ExchangeRate<'info> {
+    /// The collateral mint whose FLT exchange rate is queried.
+    pub collateral_mint: Box<Account<'info, Mint>>,
+    /// The FLT mint corresponding to this collateral.
+    #[account(seeds = [b"flt_mint", collateral_mint.key().as_ref()], bump)]
+    pub flt_mint: Box<Account<'info, Mint>>,
+    /// The vault token account holding the collateral.
+    #[account(seeds = [b"vault_token", collateral_mint.key().as_ref()], bump)]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    /// The reward pool for this collateral.
+    #[account(seeds = [b"reward_pool", collateral_mint.key().as_ref()], bump = reward_pool.bump)]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGovernanceParameters<'info> {
+    /// Only the admin (as stored in the Governance account) can update parameters.
+    #[account(mut, signer, address = governance.admin)]
+    pub admin: AccountInfo<'info>,
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+}
+
+#[derive(Accounts)]
+pub struct SetManualPrice<'info> {
+    /// Only the risk manager can push manual prices.
+    #[account(mut, address = governance.roles.risk_manager)]
+    pub risk_manager: Signer<'info>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The collateral's risk configuration.
+    #[account(
+        seeds = [b"collateral_config", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+    /// The manual price, derived as: seeds = [b"manual_price", collateral_mint].
+    #[account(
+        init_if_needed,
+        payer = risk_manager,
+        space = ManualPrice::LEN,
+        seeds = [b"manual_price", collateral_config.collateral_mint.as_ref()],
+        bump
+    )]
+    pub manual_price: Box<Account<'info, ManualPrice>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeParameters<'info> {
+    /// Only the fee manager can change protocol fees.
+    #[account(address = governance.roles.fee_manager)]
+    pub fee_manager: Signer<'info>,
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+}
+
+#[derive(Accounts)]
+pub struct SetLiquidationGraceSlots<'info> {
+    /// Only the risk manager can change the grace period.
+    #[account(address = governance.roles.risk_manager)]
+    pub risk_manager: Signer<'info>,
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+}
+
+#[derive(Accounts)]
+pub struct SetRoles<'info> {
+    /// Only the admin can assign roles.
+    #[account(address = governance.admin)]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+}
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    /// The guardian or the admin.
+    #[account(
+        constraint = authority.key() == governance.roles.guardian
+            || authority.key() == governance.admin @ CustomError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    /// Only the current admin can nominate a successor.
+    #[account(address = governance.admin)]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    /// The nominee recorded by `propose_admin`.
+    #[account(address = governance.pending_admin @ CustomError::NotPendingAdmin)]
+    pub pending_admin: Signer<'info>,
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+}
+
+#[derive(Accounts)]
+pub struct QueueParameterChange<'info> {
+    /// The role owning the change, or the admin; checked in the handler.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The pending change, derived as: seeds = [b"pending_change", next_change_id.to_le_bytes()].
+    #[account(
+        init,
+        payer = authority,
+        space = PendingChange::LEN,
+        seeds = [b"pending_change".as_ref(), &governance.next_change_id.to_le_bytes()],
+        bump
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteParameterChange<'info> {
+    /// The role owning the change, or the admin; checked in the handler.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The pending change being executed, closed to the authority.
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"pending_change".as_ref(), &pending_change.id.to_le_bytes()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+    /// The collateral config targeted by a collateral change; omitted for governance changes.
+    #[account(
+        mut,
+        seeds = [b"collateral_config", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Option<Box<Account<'info, CollateralConfig>>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelParameterChange<'info> {
+    /// The role owning the change, or the admin; checked in the handler.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The pending change being cancelled, closed to the authority.
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"pending_change".as_ref(), &pending_change.id.to_le_bytes()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+}
+
+#[derive(Accounts)]
+pub struct DepositVotes<'info> {
+    /// The FLT holder depositing voting power.
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The FLT mint of the governance voting collateral.
+    #[account(seeds = [b"flt_mint", governance.vote_collateral_mint.as_ref()], bump)]
+    pub flt_mint: Box<Account<'info, Mint>>,
+    /// The voter's FLT token account.
+    #[account(mut, token::mint = flt_mint, token::authority = voter)]
+    pub voter_flt_account: Box<Account<'info, TokenAccount>>,
+    /// The voter's record, derived as: seeds = [b"voter", voter.key().as_ref()].
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoterRecord::LEN,
+        seeds = [b"voter", voter.key().as_ref()],
+        bump
+    )]
+    pub voter_record: Box<Account<'info, VoterRecord>>,
+    /// The escrow holding the voter's FLT, owned by the voter record.
+    #[account(
+        init_if_needed,
+        payer = voter,
+        seeds = [b"vote_escrow", voter.key().as_ref()],
+        bump,
+        token::mint = flt_mint,
+        token::authority = voter_record
+    )]
+    pub vote_escrow: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVotes<'info> {
+    /// The voter withdrawing FLT.
+    pub voter: Signer<'info>,
     /// The governance account.
+    #[account(seeds = [b"governance"], bump = governance.bump)]
     pub governance: Box<Account<'info, Governance>>,
-    /// Global reward pool account.
-    #[account(mut)]
-    pub reward_pool: Box<Account<'info, RewardPool>>,
+    /// The FLT mint of the governance voting collateral.
+    #[account(seeds = [b"flt_mint", governance.vote_collateral_mint.as_ref()], bump)]
+    pub flt_mint: Box<Account<'info, Mint>>,
+    /// The voter's FLT token account receiving the withdrawal.
+    #[account(mut, token::mint = flt_mint)]
+    pub voter_flt_account: Box<Account<'info, TokenAccount>>,
+    /// The voter's record.
+    #[account(mut, seeds = [b"voter", voter.key().as_ref()], bump = voter_record.bump)]
+    pub voter_record: Box<Account<'info, VoterRecord>>,
+    /// The escrow holding the voter's FLT.
+    #[account(mut, seeds = [b"vote_escrow", voter.key().as_ref()], bump, token::mint = flt_mint)]
+    pub vote_escrow: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct Unstake<'info> {
-    /// The user unstaking collateral.
+pub struct CreateProposal<'info> {
+    /// The proposer, who must hold escrowed voting power.
     #[account(mut)]
-    pub user: Signer<'info>,
-    /// The user's token account to receive collateral.
+    pub proposer: Signer<'info>,
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Box<Account<'info, Governance>>,
+    /// The FLT mint of the governance voting collateral; its supply is snapshotted for quorum.
+    #[account(seeds = [b"flt_mint", governance.vote_collateral_mint.as_ref()], bump)]
+    pub flt_mint: Box<Account<'info, Mint>>,
+    /// The proposer's voter record.
+    #[account(seeds = [b"voter", proposer.key().as_ref()], bump = voter_record.bump)]
+    pub voter_record: Box<Account<'info, VoterRecord>>,
+    /// The proposal, derived as: seeds = [b"proposal", next_proposal_id.to_le_bytes()].
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::LEN,
+        seeds = [b"proposal".as_ref(), &governance.next_proposal_id.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    /// The voter.
     #[account(mut)]
-    pub user_token_account: Box<Account<'info, TokenAccount>>,
-    /// The vault PDA account.
+    pub voter: Signer<'info>,
+    /// The proposal being voted on.
     #[account(
         mut,
-        seeds = [b"vault", staker.collateral_mint.as_ref()],
+        seeds = [b"proposal".as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    /// The voter's record.
+    #[account(mut, seeds = [b"voter", voter.key().as_ref()], bump = voter_record.bump)]
+    pub voter_record: Box<Account<'info, VoterRecord>>,
+    /// One vote per voter and proposal, derived as: seeds = [b"vote", proposal, voter].
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
         bump
     )]
-    pub vault_account: Box<Account<'info, VaultAccount>>,
-    /// The vault token account from which collateral is withdrawn.
-    #[account(mut)]
-    pub vault_token_account: Box<Account<'info, TokenAccount>>,
-    /// The staker record.
-    #[account(mut, seeds = [b"staker", user.key().as_ref(), staker.collateral_mint.as_ref()], bump)]
-    pub staker: Box<Account<'info, Staker>>,
-    /// Global reward pool account.
-    #[account(mut)]
-    pub reward_pool: Box<Account<'info, RewardPool>>,
-    pub token_program: Program<'info, Token>,
+    pub vote_record: Box<Account<'info, VoteRecord>>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateGovernanceParameters<'info> {
-    /// Only the admin (as stored in the Governance account) can update parameters.
-    #[account(mut, signer, address = governance.admin)]
-    pub admin: AccountInfo<'info>,
-    #[account(mut)]
+pub struct ExecuteProposal<'info> {
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
     pub governance: Box<Account<'info, Governance>>,
+    /// The proposal being executed.
+    #[account(
+        mut,
+        seeds = [b"proposal".as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    /// The collateral config targeted by a collateral change; omitted for governance changes.
+    #[account(
+        mut,
+        seeds = [b"collateral_config", collateral_config.collateral_mint.as_ref()],
+        bump = collateral_config.bump
+    )]
+    pub collateral_config: Option<Box<Account<'info, CollateralConfig>>>,
 }
 
 //
 // Data Accounts
 //
 
+/// The vault PDA acting as authority over a collateral's vault token account.
 #[account]
 pub struct VaultAccount {
     pub bump: u8,
 }
 
+impl VaultAccount {
+    // 8 + 1 = 9 bytes.
+    pub const LEN: usize = 8 + 1;
+}
+
+/// Stores the bump of the per-collateral FLT mint PDA.
 #[account]
 pub struct MintWrapper {
     pub bump: u8,
 }
 
+impl MintWrapper {
+    // 8 + 1 = 9 bytes.
+    pub const LEN: usize = 8 + 1;
+}
+
+/// How a loan is secured.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LoanMode {
+    /// Repaid within the same transaction, enforced via instruction introspection.
+    Flash,
+    /// Slot-based term loan backed by the borrower's staked collateral.
+    Term,
+}
+
 /// The loan record, with slot‑based timing and a reentrancy flag.
+/// Derived as: seeds = [b"loan", borrower, collateral_mint, nonce.to_le_bytes()].
 #[account]
 pub struct Loan {
     pub borrower: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub nonce: u64,
     pub amount: u64,
     pub start_slot: u64,
     pub due_slot: u64,
     pub active: bool,
+    pub mode: LoanMode,
+    pub bump: u8,
 }
 
 impl Loan {
-    // 8 + 32 + 8 + 8 + 8 + 1 = 65 bytes.
-    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 1;
+    // 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 1 + 1 = 107 bytes.
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 1 + 1;
+}
+
+/// Per-borrower loan counters, allowing several concurrent loans.
+#[account]
+pub struct BorrowerState {
+    pub next_loan_nonce: u64,              // nonce used to derive the borrower's next loan PDA
+    pub open_loans: u64,                   // number of loans not yet repaid or liquidated
+    pub bump: u8,
 }
 
+impl BorrowerState {
+    // 8 + 8 + 8 + 1 = 25 bytes.
+    pub const LEN: usize = 8 + 8 + 8 + 1;
+}
+
+/// Pause flag for `stake`.
+pub const PAUSE_STAKE: u8 = 1 << 0;
+/// Pause flag for `unstake`.
+pub const PAUSE_UNSTAKE: u8 = 1 << 1;
+/// Pause flag for `borrow` and `flash_loan`.
+pub const PAUSE_BORROW: u8 = 1 << 2;
+/// Pause flag for `repay`. Only the admin can set it.
+pub const PAUSE_REPAY: u8 = 1 << 3;
+/// Pause flag for `liquidate`.
+pub const PAUSE_LIQUIDATE: u8 = 1 << 4;
+/// Pause flag for `compound_rewards` and `claim_rewards`.
+pub const PAUSE_COMPOUND: u8 = 1 << 5;
+
 /// Governance parameters for the protocol.
+/// Per-collateral risk parameters live in `CollateralConfig`.
 #[account]
 pub struct Governance {
     pub admin: Pubkey,
-    pub flash_loan_fee_bps: u64,         // default fee (unused in dynamic mode)
-    pub liquidation_penalty_bps: u64,      // penalty fee per overdue slot (in basis points)
+    pub pending_admin: Pubkey,             // nominee awaiting accept_admin (default when none)
+    pub roles: GovernanceRoles,
     pub liquidation_grace_slots: u64,      // grace period (in slots)
     pub compound_rate_numerator: u64,      // for auto-compounding rewards
     pub compound_rate_denominator: u64,    // for auto-compounding rewards
-    pub max_borrow_ratio: u64,             // maximum borrowable amount as a percentage (in basis points) of collateral
-    pub supported_collaterals: Vec<Pubkey>,// list of approved collateral mints
+    pub reward_share_bps: u64,             // share of each fee reserved for claim_rewards (in basis points)
+    pub insurance_share_bps: u64,          // share of each fee set aside in the insurance fund (in basis points)
+    pub timelock_delay_slots: u64,         // delay between queueing and executing a parameter change
+    pub next_change_id: u64,               // id used to derive the next PendingChange PDA
+    pub vote_collateral_mint: Pubkey,      // collateral whose FLT carries voting power
+    pub voting_period_slots: u64,          // length of a proposal's voting window
+    pub quorum_bps: u64,                   // votes cast needed, as a share of the snapshotted FLT supply
+    pub approval_bps: u64,                 // yes votes needed, as a share of votes cast
+    pub next_proposal_id: u64,             // id used to derive the next Proposal PDA
+    pub paused: u8,                        // bitmap of paused instruction families (PAUSE_* flags)
+    pub bump: u8,
 }
 
 impl Governance {
-    // For example, assuming up to 10 supported collaterals.
-    pub const LEN: usize = 8 + 32 + (6 * 8) + 4 + (32 * 10);
+    // 8 + 32 + 32 + 128 + (7 * 8) + 32 + (4 * 8) + 1 + 1 = 322 bytes.
+    pub const LEN: usize = 8 + 32 + 32 + GovernanceRoles::LEN + (7 * 8) + 32 + (4 * 8) + 1 + 1;
+
+    /// Fail with `ProtocolPaused` if any of `flag` is paused.
+    pub fn require_not_paused(&self, flag: u8) -> Result<()> {
+        require!(self.paused & flag == 0, CustomError::ProtocolPaused);
+        Ok(())
+    }
+
+    /// Fail with `Unauthorized` unless `signer` is the admin or the role owning `change`.
+    pub fn require_change_authority(&self, change: &ParameterChange, signer: Pubkey) -> Result<()> {
+        require!(
+            signer == self.admin || signer == change.authority(self),
+            CustomError::Unauthorized
+        );
+        Ok(())
+    }
+
+    /// Overwrite the adjustable parameters, leaving admin, counters and bump untouched.
+    pub fn apply(&mut self, params: GovernanceParams) {
+        self.liquidation_grace_slots = params.liquidation_grace_slots;
+        self.compound_rate_numerator = params.compound_rate_numerator;
+        self.compound_rate_denominator = params.compound_rate_denominator;
+        self.reward_share_bps = params.reward_share_bps;
+        self.insurance_share_bps = params.insurance_share_bps;
+        self.timelock_delay_slots = params.timelock_delay_slots;
+        self.vote_collateral_mint = params.vote_collateral_mint;
+        self.voting_period_slots = params.voting_period_slots;
+        self.quorum_bps = params.quorum_bps;
+        self.approval_bps = params.approval_bps;
+    }
+
+    /// The adjustable parameters currently in effect.
+    pub fn params(&self) -> GovernanceParams {
+        GovernanceParams {
+            liquidation_grace_slots: self.liquidation_grace_slots,
+            compound_rate_numerator: self.compound_rate_numerator,
+            compound_rate_denominator: self.compound_rate_denominator,
+            reward_share_bps: self.reward_share_bps,
+            insurance_share_bps: self.insurance_share_bps,
+            timelock_delay_slots: self.timelock_delay_slots,
+            vote_collateral_mint: self.vote_collateral_mint,
+            voting_period_slots: self.voting_period_slots,
+            quorum_bps: self.quorum_bps,
+            approval_bps: self.approval_bps,
+        }
+    }
+}
+
+/// Keys holding the narrow operational permissions. All default to the admin.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct GovernanceRoles {
+    pub fee_manager: Pubkey,               // fee curves and reward share
+    pub risk_manager: Pubkey,              // borrow ratios, penalties, caps, feeds and grace period
+    pub listing_manager: Pubkey,           // list, delist and listing status of collaterals
+    pub guardian: Pubkey,                  // pause only
+}
+
+impl GovernanceRoles {
+    // 4 * 32 = 128 bytes.
+    pub const LEN: usize = 4 * 32;
+}
+
+/// Adjustable governance parameters, set at initialization, through the timelock or by vote.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct GovernanceParams {
+    pub liquidation_grace_slots: u64,
+    pub compound_rate_numerator: u64,
+    pub compound_rate_denominator: u64,
+    pub reward_share_bps: u64,
+    pub insurance_share_bps: u64,
+    pub timelock_delay_slots: u64,
+    pub vote_collateral_mint: Pubkey,
+    pub voting_period_slots: u64,
+    pub quorum_bps: u64,
+    pub approval_bps: u64,
+}
+
+impl GovernanceParams {
+    // (6 * 8) + 32 + (3 * 8) = 104 bytes.
+    pub const LEN: usize = (6 * 8) + 32 + (3 * 8);
+
+    /// Reject fee shares that together exceed the whole fee.
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            math::add(self.reward_share_bps, self.insurance_share_bps)? <= math::BPS_DENOMINATOR,
+            CustomError::InvalidFeeShare
+        );
+        Ok(())
+    }
+}
+
+/// A parameter change, applied directly, through the timelock or by a passed proposal.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum ParameterChange {
+    /// Replace the protocol-wide governance parameters. Admin.
+    Governance(GovernanceParams),
+    /// Replace a collateral's risk parameters and listing status. Admin.
+    Collateral {
+        collateral_mint: Pubkey,
+        params: CollateralParams,
+        status: CollateralStatus,
+    },
+    /// Set the stakers' and the insurance fund's shares of fees. Fee manager.
+    FeeParameters { reward_share_bps: u64, insurance_share_bps: u64 },
+    /// Set the liquidation grace period. Risk manager.
+    LiquidationGraceSlots(u64),
+    /// Replace a collateral's fee curve. Fee manager.
+    CollateralFeeCurve { collateral_mint: Pubkey, fee_curve: FeeCurve },
+    /// Replace a collateral's ratios, penalty, cap and price feeds. Risk manager.
+    CollateralRisk { collateral_mint: Pubkey, params: CollateralRiskParams },
+    /// Change a collateral's listing status. Listing manager.
+    ListingStatus { collateral_mint: Pubkey, status: CollateralStatus },
+}
+
+impl ParameterChange {
+    // 1 (variant) + the larger variant: 32 + CollateralParams::LEN + 1.
+    pub const LEN: usize = 1 + 32 + CollateralParams::LEN + 1;
+
+    /// The key that may apply this change directly and queue, execute or cancel it.
+    pub fn authority(&self, governance: &Governance) -> Pubkey {
+        match self {
+            Self::FeeParameters { .. } | Self::CollateralFeeCurve { .. } => governance.roles.fee_manager,
+            Self::LiquidationGraceSlots(_) | Self::CollateralRisk { .. } => governance.roles.risk_manager,
+            Self::ListingStatus { .. } => governance.roles.listing_manager,
+            Self::Governance(_) | Self::Collateral { .. } => governance.admin,
+        }
+    }
+
+    /// The collateral a change targets, if any.
+    pub fn collateral_mint(&self) -> Option<Pubkey> {
+        match self {
+            Self::Collateral { collateral_mint, .. }
+            | Self::CollateralFeeCurve { collateral_mint, .. }
+            | Self::CollateralRisk { collateral_mint, .. }
+            | Self::ListingStatus { collateral_mint, .. } => Some(*collateral_mint),
+            Self::Governance(_) | Self::FeeParameters { .. } | Self::LiquidationGraceSlots(_) => None,
+        }
+    }
+}
+
+/// Timelocked parameter change.
+/// Derived as: seeds = [b"pending_change", id.to_le_bytes()].
+#[account]
+pub struct PendingChange {
+    pub id: u64,
+    pub change: ParameterChange,
+    pub eta_slot: u64,                     // first slot at which the change can be executed
+    pub bump: u8,
+}
+
+impl PendingChange {
+    // 8 + 8 + ParameterChange::LEN + 8 + 1 bytes.
+    pub const LEN: usize = 8 + 8 + ParameterChange::LEN + 8 + 1;
+}
+
+/// An FLT-weighted governance proposal.
+/// Derived as: seeds = [b"proposal", id.to_le_bytes()].
+#[account]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub change: ParameterChange,
+    pub snapshot_slot: u64,                // only FLT escrowed before this slot can vote
+    pub snapshot_supply: u64,              // voting FLT supply at creation, the quorum base
+    pub voting_end_slot: u64,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl Proposal {
+    // 8 + 8 + 32 + ParameterChange::LEN + (5 * 8) + 1 + 1 bytes.
+    pub const LEN: usize = 8 + 8 + 32 + ParameterChange::LEN + (5 * 8) + 1 + 1;
+}
+
+/// A voter's escrowed FLT voting power.
+/// Derived as: seeds = [b"voter", owner].
+#[account]
+pub struct VoterRecord {
+    pub owner: Pubkey,
+    pub amount: u64,                       // FLT held in the vote escrow
+    pub last_deposit_slot: u64,            // deposits count only for proposals created after this slot
+    pub unlock_slot: u64,                  // end of the latest voting period this record voted in
+    pub bump: u8,
+}
+
+impl VoterRecord {
+    // 8 + 32 + (3 * 8) + 1 = 65 bytes.
+    pub const LEN: usize = 8 + 32 + (3 * 8) + 1;
+}
+
+/// A single vote, preventing a voter from voting twice on the same proposal.
+/// Derived as: seeds = [b"vote", proposal, voter].
+#[account]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub approve: bool,
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    // 8 + 32 + 32 + 8 + 1 + 1 = 82 bytes.
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 1 + 1;
+}
+
+/// Listing status of a collateral.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CollateralStatus {
+    /// Accepts new stakes and loans.
+    Active,
+    /// Existing positions can be unwound; no new stakes or loans.
+    ReduceOnly,
+    /// Only repayments and liquidations are allowed.
+    Frozen,
+}
+
+/// Fallback price source of a collateral.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SecondaryOracle {
+    /// No fallback; a stale Pyth feed halts pricing.
+    None,
+    /// A second Pyth price account.
+    Pyth(Pubkey),
+    /// The governance-pushed `ManualPrice` PDA of the collateral.
+    Manual,
+}
+
+impl SecondaryOracle {
+    // 1 (variant) + 32 = 33 bytes.
+    pub const LEN: usize = 1 + 32;
+}
+
+/// Governance-pushed price of a collateral, usable as its secondary oracle.
+/// Derived as: seeds = [b"manual_price", collateral_mint].
+#[account]
+pub struct ManualPrice {
+    pub collateral_mint: Pubkey,
+    pub price: u64,                        // normalized to oracle::PRICE_DECIMALS decimals
+    pub publish_time: i64,                 // unix timestamp of the last push
+    pub bump: u8,
+}
+
+impl ManualPrice {
+    // 8 + 32 + 8 + 8 + 1 = 57 bytes.
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 1;
+
+    /// The pushed price, if it is no older than `max_age_secs`.
+    pub fn normalized_price(&self, max_age_secs: u64, current_time: u64) -> Result<u64> {
+        let age = (current_time as i64).saturating_sub(self.publish_time).unsigned_abs();
+        require!(age <= max_age_secs, CustomError::OraclePriceStale);
+        Ok(self.price)
+    }
+}
+
+/// Kinked flash loan fee curve over utilization, in the style of Aave's rate model.
+/// The fee rises linearly by `slope1_bps` from 0% to the optimal utilization, then by
+/// `slope2_bps` from there to 100%. It is continuous at the kink.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct FeeCurve {
+    pub base_fee_bps: u64,                 // fee at 0% utilization
+    pub slope1_bps: u64,                   // fee added between 0% and the optimal utilization
+    pub slope2_bps: u64,                   // fee added between the optimal utilization and 100%
+    pub optimal_utilization_bps: u64,      // the kink, strictly between 0 and 10,000
+    pub reference_price: u64,              // normalized price leaving the fee unchanged; 0 disables the oracle adjustment
+}
+
+impl FeeCurve {
+    // 5 * 8 = 40 bytes.
+    pub const LEN: usize = 5 * 8;
+
+    /// Reject kinks at or beyond the ends of the utilization range.
+    pub fn validate(&self) -> Result<()> {
+        self.fee_bps(0).map(|_| ())
+    }
+
+    /// Fee in basis points at `utilization_bps` (0..=10,000). See `math::kinked_fee_bps`.
+    pub fn fee_bps(&self, utilization_bps: u64) -> Result<u64> {
+        math::kinked_fee_bps(
+            self.base_fee_bps,
+            self.slope1_bps,
+            self.slope2_bps,
+            self.optimal_utilization_bps,
+            utilization_bps,
+        )
+    }
+}
+
+/// Risk parameters supplied when listing or updating a collateral.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct CollateralParams {
+    pub max_borrow_ratio: u64,             // maximum borrowable amount as a percentage (in basis points) of collateral
+    pub liquidation_penalty_bps: u64,      // penalty fee per overdue slot (in basis points)
+    pub borrow_cap: u64,                   // maximum principal outstanding against this collateral
+    pub pyth_feed: Pubkey,                 // Pyth price account for this collateral
+    pub max_price_age_secs: u64,           // oldest accepted Pyth price (in seconds)
+    pub secondary_oracle: SecondaryOracle, // fallback price source when the Pyth feed is stale
+    pub max_deviation_bps: u64,            // largest accepted gap between the two sources (in basis points)
+    pub fee_curve: FeeCurve,
+}
+
+/// Risk parameters managed by the risk manager.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct CollateralRiskParams {
+    pub max_borrow_ratio: u64,
+    pub liquidation_penalty_bps: u64,
+    pub borrow_cap: u64,
+    pub pyth_feed: Pubkey,
+    pub max_price_age_secs: u64,
+    pub secondary_oracle: SecondaryOracle,
+    pub max_deviation_bps: u64,
+}
+
+impl CollateralParams {
+    // 8 + 8 + 8 + 32 + 8 + 33 + 8 + 40 = 145 bytes.
+    pub const LEN: usize = 8 + 8 + 8 + 32 + 8 + SecondaryOracle::LEN + 8 + FeeCurve::LEN;
+}
+
+/// Per-collateral risk configuration.
+/// Derived as: seeds = [b"collateral_config", collateral_mint].
+#[account]
+pub struct CollateralConfig {
+    pub collateral_mint: Pubkey,
+    pub status: CollateralStatus,
+    pub max_borrow_ratio: u64,             // maximum borrowable amount as a percentage (in basis points) of collateral
+    pub liquidation_penalty_bps: u64,      // penalty fee per overdue slot (in basis points)
+    pub borrow_cap: u64,                   // maximum principal outstanding against this collateral
+    pub pyth_feed: Pubkey,                 // Pyth price account for this collateral
+    pub max_price_age_secs: u64,           // oldest accepted Pyth price (in seconds)
+    pub secondary_oracle: SecondaryOracle, // fallback price source when the Pyth feed is stale
+    pub max_deviation_bps: u64,            // largest accepted gap between the two sources (in basis points)
+    pub fee_curve: FeeCurve,
+    pub bump: u8,
 }
 
-/// Global reward pool tracking staked collateral, accrued fees, active loans, and an update counter.
+impl CollateralConfig {
+    // 8 + 32 + 1 + 8 + 8 + 8 + 32 + 8 + 33 + 8 + 40 + 1 = 187 bytes.
+    pub const LEN: usize = 8 + 32 + 1 + 8 + 8 + 8 + 32 + 8 + SecondaryOracle::LEN + 8 + FeeCurve::LEN + 1;
+
+    /// Overwrite the risk parameters, leaving mint, status and bump untouched.
+    pub fn apply(&mut self, params: CollateralParams) {
+        self.max_borrow_ratio = params.max_borrow_ratio;
+        self.liquidation_penalty_bps = params.liquidation_penalty_bps;
+        self.borrow_cap = params.borrow_cap;
+        self.pyth_feed = params.pyth_feed;
+        self.max_price_age_secs = params.max_price_age_secs;
+        self.secondary_oracle = params.secondary_oracle;
+        self.max_deviation_bps = params.max_deviation_bps;
+        self.fee_curve = params.fee_curve;
+    }
+
+    /// Current risk parameters and fee curve.
+    pub fn params(&self) -> CollateralParams {
+        CollateralParams {
+            max_borrow_ratio: self.max_borrow_ratio,
+            liquidation_penalty_bps: self.liquidation_penalty_bps,
+            borrow_cap: self.borrow_cap,
+            pyth_feed: self.pyth_feed,
+            max_price_age_secs: self.max_price_age_secs,
+            secondary_oracle: self.secondary_oracle,
+            max_deviation_bps: self.max_deviation_bps,
+            fee_curve: self.fee_curve,
+        }
+    }
+
+    /// Overwrite the risk manager's parameters, leaving the fee curve untouched.
+    pub fn apply_risk(&mut self, params: CollateralRiskParams) {
+        self.max_borrow_ratio = params.max_borrow_ratio;
+        self.liquidation_penalty_bps = params.liquidation_penalty_bps;
+        self.borrow_cap = params.borrow_cap;
+        self.pyth_feed = params.pyth_feed;
+        self.max_price_age_secs = params.max_price_age_secs;
+        self.secondary_oracle = params.secondary_oracle;
+        self.max_deviation_bps = params.max_deviation_bps;
+    }
+}
+
+/// Per-collateral reward pool tracking staked collateral, accrued fees, active loans, and an update counter.
+/// All amounts are denominated in the pool's collateral mint.
 #[account]
 pub struct RewardPool {
+    pub collateral_mint: Pubkey,
     pub total_staked: u64,
     pub accrued_fees: u64,
     pub active_loan_total: u64,
     pub update_counter: u64,
+    pub acc_fee_per_share: u128,           // fees per unit of reward weight, scaled by ACC_FEE_PRECISION
+    pub reserved_rewards: u64,             // fees credited to stakers but not yet claimed
+    pub insurance_fund: u64,               // fees set aside to cover bad debt, held in the insurance token account
+    pub bad_debt: u64,                     // cumulative principal the insurance fund could not cover, socialized across FLT holders
+    pub flash_loan_active: bool,           // set while a flash_loan callback is executing
+    pub bump: u8,
 }
 
 impl RewardPool {
-    // 8 + 8 + 8 + 8 = 32 bytes plus discriminator = 40 bytes total.
-    pub const LEN: usize = 8 + 8 + 8 + 8;
+    // 32 + 8 + 8 + 8 + 8 + 16 + 8 + 8 + 8 + 1 + 1 = 106 bytes plus discriminator = 114 bytes total.
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 8 + 16 + 8 + 8 + 8 + 1 + 1;
+
+    /// Write off a liquidated loan's `principal`, of which the seized position left `shortfall`
+    /// uncovered, and run the shortfall through the loss waterfall. Returns
+    /// `(from_insurance, socialized)`: the caller moves `from_insurance` from the insurance fund's
+    /// token account back into the vault, and only `socialized` is recorded as bad debt.
+    pub fn write_off(&mut self, principal: u64, shortfall: u64) -> Result<(u64, u64)> {
+        self.active_loan_total = math::sub(self.active_loan_total, principal)?;
+        let (from_insurance, socialized) = math::loss_waterfall(shortfall, self.insurance_fund)?;
+        self.insurance_fund = math::sub(self.insurance_fund, from_insurance)?;
+        self.bad_debt = math::add(self.bad_debt, socialized)?;
+        Ok((from_insurance, socialized))
+    }
 }
 
 /// Record for an individual staker.
+/// The staked FLT sits in the staker's escrow, derived as:
+/// seeds = [b"stake_escrow", owner, collateral_mint], so the lock, the reward weight and term
+/// borrowing all refer to FLT the program holds.
 #[account]
 pub struct Staker {
-    pub staked_amount: u64,
+    pub staked_amount: u64,                // reward weight (boosted stake plus compounded rewards)
     pub collateral_mint: Pubkey,
     pub last_compound_slot: u64,
     pub lock_end_slot: u64,
+    pub reward_debt: u128,                 // staked_amount * acc_fee_per_share at the last settlement
+    pub pending_rewards: u64,              // settled fees not yet claimed
+    pub escrowed_flt: u64,                 // FLT held in the stake escrow
+    pub borrowed: u64,                     // term loan principal outstanding against the escrow
 }
 
 impl Staker {
-    // 8 + 8 + 32 + 8 + 8 = 64 bytes.
-    pub const LEN: usize = 8 + 8 + 32 + 8 + 8;
+    // 8 + 8 + 32 + 8 + 8 + 16 + 8 + 8 + 8 = 104 bytes.
+    pub const LEN: usize = 8 + 8 + 32 + 8 + 8 + 16 + 8 + 8 + 8;
+
+    /// Release `flt_amount` of escrowed FLT for `unstake` or `withdraw_stake`: the lock must have
+    /// expired and no term principal may be outstanding. Returns the reward weight removed.
+    pub fn release_escrow(&mut self, flt_amount: u64, acc_fee_per_share: u128, current_slot: u64) -> Result<u64> {
+        require!(current_slot >= self.lock_end_slot, CustomError::StakingLocked);
+        require!(self.borrowed == 0, CustomError::StakeBackingLoan);
+        self.remove_escrow(flt_amount, acc_fee_per_share)
+    }
+
+    /// Take `flt_amount` out of the escrow together with the same share of reward weight,
+    /// settling fees earned so far. Returns the reward weight removed.
+    pub fn remove_escrow(&mut self, flt_amount: u64, acc_fee_per_share: u128) -> Result<u64> {
+        require!(flt_amount <= self.escrowed_flt, CustomError::InsufficientStakedAmount);
+        self.settle_rewards(acc_fee_per_share)?;
+        let weight = math::escrow_weight(self.staked_amount, flt_amount, self.escrowed_flt)?;
+        self.staked_amount = math::sub(self.staked_amount, weight)?;
+        self.escrowed_flt = math::sub(self.escrowed_flt, flt_amount)?;
+        self.sync_reward_debt(acc_fee_per_share)?;
+        Ok(weight)
+    }
+
+    /// Move fees earned by the current weight since the last settlement into `pending_rewards`.
+    pub fn settle_rewards(&mut self, acc_fee_per_share: u128) -> Result<()> {
+        let accumulated = math::accumulated_rewards(self.staked_amount, acc_fee_per_share)?;
+        let earned = math::to_u64(accumulated.checked_sub(self.reward_debt).ok_or(CustomError::MathOverflow)?)?;
+        self.pending_rewards = math::add(self.pending_rewards, earned)?;
+        Ok(())
+    }
+
+    /// Reset the reward debt after the weight changes, so only future fees accrue.
+    pub fn sync_reward_debt(&mut self, acc_fee_per_share: u128) -> Result<()> {
+        self.reward_debt = math::accumulated_rewards(self.staked_amount, acc_fee_per_share)?;
+        Ok(())
+    }
+}
+
+//
+// Events
+//
+
+/// Collateral deposited and FLT minted.
+#[event]
+pub struct Staked {
+    pub user: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub amount: u64,                       // collateral deposited
+    pub flt_minted: u64,
+    pub reward_weight: u64,                // reward weight added, including any early-adopter boost
+    pub lock_end_slot: u64,
+    pub slot: u64,
+}
+
+/// FLT burned and collateral redeemed.
+#[event]
+pub struct Unstaked {
+    pub user: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub flt_burned: u64,
+    pub collateral_returned: u64,
+    pub reward_weight_removed: u64,
+    pub slot: u64,
+}
+
+/// FLT held outside an escrow burned for collateral by `redeem`.
+#[event]
+pub struct Redeemed {
+    pub user: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub flt_burned: u64,
+    pub collateral_returned: u64,
+    pub slot: u64,
+}
+
+/// Escrowed FLT moved to the staker's own FLT account.
+#[event]
+pub struct StakeWithdrawn {
+    pub user: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub flt_amount: u64,
+    pub reward_weight_removed: u64,
+    pub slot: u64,
+}
+
+/// Liquidity lent by `borrow` or `flash_loan`.
+#[event]
+pub struct Borrowed {
+    pub borrower: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub loan: Option<Pubkey>,              // Loan PDA; None for single-instruction flash loans
+    pub mode: LoanMode,
+    pub amount: u64,                       // principal
+    pub fee: u64,
+    pub fee_bps: u64,                      // fee rate charged, after the oracle adjustment
+    pub utilization_bps: u64,              // post-borrow utilization the fee curve was read at
+    pub oracle_price: u64,                 // normalized price used (oracle::PRICE_DECIMALS)
+    pub due_slot: u64,
+    pub slot: u64,
+}
+
+/// Loan repaid, in full or in part.
+#[event]
+pub struct Repaid {
+    pub borrower: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub loan: Pubkey,
+    pub principal: u64,
+    pub penalty: u64,                      // late penalty credited to the reward pool
+    pub amount: u64,                       // total transferred by the borrower
+    pub remaining_principal: u64,          // zero once the loan is closed
+    pub slot: u64,
+}
+
+/// Term loan's due slot pushed forward.
+#[event]
+pub struct LoanExtended {
+    pub borrower: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub loan: Pubkey,
+    pub principal: u64,
+    pub fee: u64,
+    pub fee_bps: u64,                      // fee rate charged, after the oracle adjustment
+    pub utilization_bps: u64,              // utilization the fee curve was read at
+    pub oracle_price: u64,                 // normalized price used (oracle::PRICE_DECIMALS)
+    pub due_slot: u64,                     // new due slot
+    pub slot: u64,
+}
+
+/// Overdue loan liquidated.
+#[event]
+pub struct Liquidated {
+    pub liquidator: Pubkey,
+    pub borrower: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub loan: Pubkey,
+    pub principal: u64,
+    pub seized: u64,                       // escrowed FLT seized from the borrower and burned
+    pub bonus: u64,                        // collateral paid to the liquidator out of the seized FLT's value
+    pub oracle_price: u64,                 // normalized price used (oracle::PRICE_DECIMALS)
+    pub slot: u64,
+}
+
+/// Uncovered principal of a liquidated loan, split by the loss waterfall.
+#[event]
+pub struct BadDebtRecorded {
+    pub collateral_mint: Pubkey,
+    pub loan: Pubkey,
+    pub shortfall: u64,                    // principal not covered by the seized FLT's value after the bonus
+    pub from_insurance: u64,               // paid by the insurance fund
+    pub socialized: u64,                   // absorbed by FLT holders through the exchange rate
+    pub insurance_fund: u64,               // insurance fund remaining
+    pub bad_debt: u64,                     // cumulative socialized bad debt of this collateral
+    pub slot: u64,
+}
+
+/// Staker reward weight compounded.
+#[event]
+pub struct RewardsCompounded {
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub reward_weight_added: u64,
+    pub reward_weight: u64,                // staker's reward weight after compounding
+    pub slot: u64,
+}
+
+/// Staker's reserved fees paid out by `claim_rewards`.
+#[event]
+pub struct RewardsClaimed {
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub amount: u64,                       // collateral transferred to the owner
+    pub slot: u64,
+}
+
+/// Governance parameters, roles, admin or pause flags changed.
+/// Carries the full resulting settings so indexers need not read the account.
+#[event]
+pub struct GovernanceUpdated {
+    pub authority: Pubkey,                 // signer, or the proposal account for a passed vote
+    pub admin: Pubkey,
+    pub roles: GovernanceRoles,
+    pub params: GovernanceParams,
+    pub paused: u8,
+    pub slot: u64,
+}
+
+/// Collateral listed with `list_collateral`.
+#[event]
+pub struct CollateralListed {
+    pub authority: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub params: CollateralParams,
+    pub slot: u64,
+}
+
+/// Collateral listing status, risk parameters or fee curve changed.
+/// Carries the full resulting settings so indexers need not read the account.
+#[event]
+pub struct CollateralUpdated {
+    pub authority: Pubkey,                 // signer, or the proposal account for a passed vote
+    pub collateral_mint: Pubkey,
+    pub status: CollateralStatus,
+    pub params: CollateralParams,
+    pub slot: u64,
+}
+
+/// Collateral delisted and its configuration closed.
+#[event]
+pub struct CollateralDelisted {
+    pub authority: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub slot: u64,
+}
+
+/// Manual secondary price pushed with `set_manual_price`.
+#[event]
+pub struct ManualPriceSet {
+    pub authority: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub price: u64,                        // normalized price (oracle::PRICE_DECIMALS)
+    pub publish_time: i64,
+    pub slot: u64,
 }
 
 //
@@ -650,4 +3228,66 @@ pub enum CustomError {
     OraclePriceUnavailable,
     #[msg("Invalid timestamp: negative value encountered.")]
     InvalidTimestamp,
+    #[msg("Flash borrow requires a matching repay later in the same transaction.")]
+    FlashRepayMissing,
+    #[msg("Flash borrow must be a top-level instruction.")]
+    FlashLoanCpiForbidden,
+    #[msg("No rewards to claim.")]
+    NoRewardsToClaim,
+    #[msg("Collateral is not accepting new positions.")]
+    CollateralNotActive,
+    #[msg("Collateral is frozen.")]
+    CollateralFrozen,
+    #[msg("Borrow cap for this collateral exceeded.")]
+    BorrowCapExceeded,
+    #[msg("Collateral still has outstanding FLT or open loans.")]
+    CollateralNotEmpty,
+    #[msg("Parameter changes must be queued through the timelock.")]
+    TimelockActive,
+    #[msg("Timelock delay has not elapsed.")]
+    TimelockNotElapsed,
+    #[msg("Collateral config does not match the queued change.")]
+    CollateralConfigMismatch,
+    #[msg("Not enough escrowed FLT voting power.")]
+    InsufficientVotingPower,
+    #[msg("Escrowed FLT is locked until voting ends.")]
+    VotesLocked,
+    #[msg("Voting on this proposal has closed.")]
+    VotingClosed,
+    #[msg("Voting on this proposal is still open.")]
+    VotingOpen,
+    #[msg("Proposal has already been executed.")]
+    ProposalAlreadyExecuted,
+    #[msg("Proposal did not reach quorum.")]
+    QuorumNotReached,
+    #[msg("Proposal did not reach the approval threshold.")]
+    ProposalRejected,
+    #[msg("Signer is not the pending admin.")]
+    NotPendingAdmin,
+    #[msg("Signer lacks the required role.")]
+    Unauthorized,
+    #[msg("This instruction is paused.")]
+    ProtocolPaused,
+    #[msg("Oracle confidence interval is too wide.")]
+    OracleConfidenceTooWide,
+    #[msg("Price account is not the feed bound to this collateral.")]
+    OracleFeedMismatch,
+    #[msg("Oracle price is stale.")]
+    OraclePriceStale,
+    #[msg("Primary and secondary oracle prices deviate too far.")]
+    OracleDeviationTooHigh,
+    #[msg("Fee curve kink must be strictly between 0% and 100% utilization.")]
+    InvalidFeeCurve,
+    #[msg("Arithmetic overflow.")]
+    MathOverflow,
+    #[msg("Only term loans can be extended.")]
+    LoanNotExtendable,
+    #[msg("Loan is overdue.")]
+    LoanOverdue,
+    #[msg("Fee shares exceed 100%.")]
+    InvalidFeeShare,
+    #[msg("Staked FLT is backing an open term loan.")]
+    StakeBackingLoan,
+    #[msg("The collateral's secondary price source was not provided.")]
+    SecondaryOracleMissing,
 }
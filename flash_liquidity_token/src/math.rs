@@ -0,0 +1,466 @@
+//
+// Math
+//
+// Fee, penalty, utilization, boost, share and reward arithmetic.
+// Every function is pure, widens to u128 for intermediate products, rounds in favour of the
+// vault and returns `CustomError::MathOverflow` instead of panicking, so off-chain Rust can
+// reproduce on-chain amounts exactly.
+//
+
+use anchor_lang::prelude::*;
+
+use crate::CustomError;
+
+/// Denominator of every basis-point value (100% == 10,000).
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Fixed-point precision of `RewardPool.acc_fee_per_share`.
+pub const ACC_FEE_PRECISION: u128 = 1_000_000_000_000;
+
+/// Early-adopter threshold, in whole collateral tokens.
+pub const EARLY_ADOPTER_WHOLE_TOKENS: u64 = 10_000;
+
+/// Reward weight multiplier for early adopters, in basis points (1.5x).
+pub const EARLY_ADOPTER_BOOST_BPS: u64 = 15_000;
+
+/// Virtual FLT and collateral added to both sides of every share conversion. A first depositor
+/// who donates collateral to the vault shares the donation with these virtual shares, so
+/// inflating the exchange rate until later deposits round down to zero costs far more than it
+/// can take.
+pub const VIRTUAL_SHARES: u64 = 1_000;
+pub const VIRTUAL_ASSETS: u64 = 1_000;
+
+/// Narrow a u128 back to u64.
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+/// `a + b`.
+pub fn add(a: u64, b: u64) -> Result<u64> {
+    a.checked_add(b).ok_or_else(|| error!(CustomError::MathOverflow))
+}
+
+/// `a - b`.
+pub fn sub(a: u64, b: u64) -> Result<u64> {
+    a.checked_sub(b).ok_or_else(|| error!(CustomError::MathOverflow))
+}
+
+/// `a * b / denominator`, rounded down.
+pub fn mul_div_floor(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    require!(denominator > 0, CustomError::MathOverflow);
+    to_u64((a as u128) * (b as u128) / denominator as u128)
+}
+
+/// `a * b / denominator`, rounded up.
+pub fn mul_div_ceil(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    require!(denominator > 0, CustomError::MathOverflow);
+    to_u64(((a as u128) * (b as u128)).div_ceil(denominator as u128))
+}
+
+/// Fee owed on `amount` at `fee_bps`. Rounded up, since the vault receives it.
+pub fn fee_amount(amount: u64, fee_bps: u64) -> Result<u64> {
+    mul_div_ceil(amount, fee_bps, BPS_DENOMINATOR)
+}
+
+/// Utilization of the vault's `assets` (see `vault_assets`) after lending out `borrowed`, in
+/// basis points capped at 100%. Rounded up, so the fee never undercharges; an empty vault counts
+/// as fully utilized.
+pub fn utilization_bps(borrowed: u64, assets: u64) -> Result<u64> {
+    if assets == 0 {
+        return Ok(BPS_DENOMINATOR);
+    }
+    Ok(mul_div_ceil(borrowed, BPS_DENOMINATOR, assets)?.min(BPS_DENOMINATOR))
+}
+
+/// Kinked fee at `utilization_bps`: `base` plus `slope1` spread linearly up to `optimal_bps`,
+/// plus `slope2` spread linearly from there to 100%. Continuous at the kink; rounded up.
+pub fn kinked_fee_bps(base: u64, slope1: u64, slope2: u64, optimal_bps: u64, utilization_bps: u64) -> Result<u64> {
+    require!(optimal_bps > 0 && optimal_bps < BPS_DENOMINATOR, CustomError::InvalidFeeCurve);
+    let utilization = utilization_bps.min(BPS_DENOMINATOR);
+    let variable = if utilization <= optimal_bps {
+        mul_div_ceil(slope1, utilization, optimal_bps)?
+    } else {
+        add(slope1, mul_div_ceil(slope2, utilization - optimal_bps, BPS_DENOMINATOR - optimal_bps)?)?
+    };
+    add(base, variable)
+}
+
+/// Penalty owed on a late repayment: `penalty_bps` of `amount` per overdue slot. Rounded up.
+pub fn late_penalty(amount: u64, overdue_slots: u64, penalty_bps: u64) -> Result<u64> {
+    let numerator = (amount as u128)
+        .checked_mul(overdue_slots as u128)
+        .and_then(|value| value.checked_mul(penalty_bps as u128))
+        .ok_or_else(|| error!(CustomError::MathOverflow))?;
+    to_u64(numerator.div_ceil(BPS_DENOMINATOR as u128))
+}
+
+/// Split a payment of `paid` against `principal`, `overdue_slots` past due, into
+/// `(principal repaid, penalty)`. The penalty is charged only on the principal repaid, so the
+/// remainder keeps accruing from the original due slot. Principal rounds down, the penalty up.
+pub fn split_repayment(paid: u64, principal: u64, overdue_slots: u64, penalty_bps: u64) -> Result<(u64, u64)> {
+    let full_penalty = late_penalty(principal, overdue_slots, penalty_bps)?;
+    if paid as u128 >= principal as u128 + full_penalty as u128 {
+        return Ok((principal, full_penalty));
+    }
+    // Each unit of principal costs 1 + overdue_slots * penalty_bps / 10,000.
+    let unit_cost_bps = BPS_DENOMINATOR as u128 + (overdue_slots as u128) * (penalty_bps as u128);
+    let mut repaid = to_u64((paid as u128) * (BPS_DENOMINATOR as u128) / unit_cost_bps)?;
+    let mut penalty = late_penalty(repaid, overdue_slots, penalty_bps)?;
+    // The rounded-up penalty can overshoot by a unit.
+    while add(repaid, penalty)? > paid {
+        repaid -= 1;
+        penalty = late_penalty(repaid, overdue_slots, penalty_bps)?;
+    }
+    Ok((repaid, penalty))
+}
+
+/// Liquidation penalty owed to a liquidator: `penalty_bps` of `amount`. Rounded down.
+pub fn liquidation_bonus(amount: u64, penalty_bps: u64) -> Result<u64> {
+    mul_div_floor(amount, penalty_bps, BPS_DENOMINATOR)
+}
+
+/// Split a defaulted loan's `principal` and liquidation `penalty` against the borrower's
+/// `position` into `(seized, bonus)`. At most `principal + penalty` is seized; the liquidator's
+/// bonus is paid from the seized amount first, capped at `penalty`.
+pub fn seize_position(position: u64, principal: u64, penalty: u64) -> Result<(u64, u64)> {
+    let seized = position.min(add(principal, penalty)?);
+    Ok((seized, penalty.min(seized)))
+}
+
+/// Principal of a liquidated loan left uncovered by the collateral `recovered` from the seized
+/// position once the liquidator's bonus has been paid out of it.
+pub fn liquidation_shortfall(principal: u64, recovered: u64) -> Result<u64> {
+    Ok(principal.saturating_sub(recovered))
+}
+
+/// Liquidate `principal` plus `penalty` (collateral units) against `escrowed_flt`, valued at
+/// `assets` and `supply`. The FLT worth the debt is seized, rounded up, and the liquidator's
+/// bonus is paid in collateral out of the seized FLT's value first. Returns
+/// `(seized_flt, bonus, shortfall)`: the FLT burned, the collateral paid to the liquidator and
+/// the principal the rest of the seized FLT does not cover.
+pub fn liquidate_escrow(
+    principal: u64,
+    penalty: u64,
+    escrowed_flt: u64,
+    assets: u64,
+    supply: u64,
+) -> Result<(u64, u64, u64)> {
+    let debt_flt = shares_for_withdrawal(add(principal, penalty)?, assets, supply)?;
+    let penalty_flt = shares_for_deposit(penalty, assets, supply)?;
+    let (seized_flt, bonus_flt) = seize_position(escrowed_flt, sub(debt_flt, penalty_flt)?, penalty_flt)?;
+    let bonus = assets_for_shares(bonus_flt, assets, supply)?;
+    let recovered = assets_for_shares(sub(seized_flt, bonus_flt)?, assets, supply)?;
+    Ok((seized_flt, bonus, liquidation_shortfall(principal, recovered)?))
+}
+
+/// Loss waterfall for a `shortfall`: the insurance fund pays first, and whatever it cannot
+/// cover is socialized across FLT holders. Returns `(from_insurance, socialized)`.
+pub fn loss_waterfall(shortfall: u64, insurance_fund: u64) -> Result<(u64, u64)> {
+    let from_insurance = shortfall.min(insurance_fund);
+    Ok((from_insurance, sub(shortfall, from_insurance)?))
+}
+
+/// Largest term principal against `collateral_value` at `max_borrow_ratio` basis points.
+/// Rounded down.
+pub fn max_borrow(collateral_value: u64, max_borrow_ratio: u64) -> Result<u64> {
+    mul_div_floor(collateral_value, max_borrow_ratio, BPS_DENOMINATOR)
+}
+
+/// Pool size below which stakers are early adopters, in base units of a mint with `decimals`.
+pub fn early_adopter_threshold(decimals: u8) -> Result<u64> {
+    let scale = 10u64.checked_pow(decimals as u32).ok_or_else(|| error!(CustomError::MathOverflow))?;
+    EARLY_ADOPTER_WHOLE_TOKENS.checked_mul(scale).ok_or_else(|| error!(CustomError::MathOverflow))
+}
+
+/// Reward weight for `flt_amount` newly minted FLT, boosted for early adopters while the vault
+/// holds less than `threshold` of collateral `assets`. Rounded down.
+pub fn boosted_weight(flt_amount: u64, assets: u64, threshold: u64) -> Result<u64> {
+    if assets < threshold {
+        mul_div_floor(flt_amount, EARLY_ADOPTER_BOOST_BPS, BPS_DENOMINATOR)
+    } else {
+        Ok(flt_amount)
+    }
+}
+
+/// Weight added by compounding `weight` for `slots` at `numerator / denominator` per slot.
+/// Rounded down.
+pub fn compound_rewards(weight: u64, numerator: u64, denominator: u64, slots: u64) -> Result<u64> {
+    require!(denominator > 0, CustomError::MathOverflow);
+    let product = (weight as u128)
+        .checked_mul(numerator as u128)
+        .and_then(|value| value.checked_mul(slots as u128))
+        .ok_or_else(|| error!(CustomError::MathOverflow))?;
+    to_u64(product / denominator as u128)
+}
+
+/// Reward weight leaving with `flt_amount` of `escrowed_flt`, boost and compounding included.
+/// Rounded up, and emptying the escrow removes all of it.
+pub fn escrow_weight(weight: u64, flt_amount: u64, escrowed_flt: u64) -> Result<u64> {
+    if flt_amount >= escrowed_flt {
+        return Ok(weight);
+    }
+    Ok(mul_div_ceil(weight, flt_amount, escrowed_flt)?.min(weight))
+}
+
+/// Total collateral backing FLT: tokens on hand plus principal lent out, less reserved rewards.
+pub fn vault_assets(vault_balance: u64, active_loan_total: u64, reserved_rewards: u64) -> Result<u64> {
+    sub(add(vault_balance, active_loan_total)?, reserved_rewards)
+}
+
+/// FLT to mint for depositing `amount` collateral, priced against `VIRTUAL_ASSETS` and
+/// `VIRTUAL_SHARES` on top of the vault. Rounded down; an empty vault mints 1:1.
+pub fn shares_for_deposit(amount: u64, assets: u64, supply: u64) -> Result<u64> {
+    mul_div_floor(amount, add(supply, VIRTUAL_SHARES)?, add(assets, VIRTUAL_ASSETS)?)
+}
+
+/// FLT worth at least `amount` collateral, priced against the same virtual offsets. Rounded up.
+pub fn shares_for_withdrawal(amount: u64, assets: u64, supply: u64) -> Result<u64> {
+    mul_div_ceil(amount, add(supply, VIRTUAL_SHARES)?, add(assets, VIRTUAL_ASSETS)?)
+}
+
+/// Collateral redeemed by burning `shares` FLT, priced against the same virtual offsets.
+/// Rounded down.
+pub fn assets_for_shares(shares: u64, assets: u64, supply: u64) -> Result<u64> {
+    mul_div_floor(shares, add(assets, VIRTUAL_ASSETS)?, add(supply, VIRTUAL_SHARES)?)
+}
+
+/// Stakers' share of `fee` at `reward_share_bps`. Rounded down; the remainder stays in the vault.
+pub fn reward_share(fee: u64, reward_share_bps: u64) -> Result<u64> {
+    mul_div_floor(fee, reward_share_bps, BPS_DENOMINATOR)
+}
+
+/// Insurance fund's share of `fee` at `insurance_share_bps`. Rounded down.
+pub fn insurance_share(fee: u64, insurance_share_bps: u64) -> Result<u64> {
+    mul_div_floor(fee, insurance_share_bps, BPS_DENOMINATOR)
+}
+
+/// Increase of `acc_fee_per_share` from distributing `reward` over `total_staked` weight.
+/// Rounded down, so claims never exceed the reserved rewards.
+pub fn acc_fee_per_share_increment(reward: u64, total_staked: u64) -> Result<u128> {
+    require!(total_staked > 0, CustomError::MathOverflow);
+    (reward as u128)
+        .checked_mul(ACC_FEE_PRECISION)
+        .map(|value| value / total_staked as u128)
+        .ok_or_else(|| error!(CustomError::MathOverflow))
+}
+
+/// Rewards accumulated by `weight` at `acc_fee_per_share`. Rounded down.
+pub fn accumulated_rewards(weight: u64, acc_fee_per_share: u128) -> Result<u128> {
+    (weight as u128)
+        .checked_mul(acc_fee_per_share)
+        .map(|value| value / ACC_FEE_PRECISION)
+        .ok_or_else(|| error!(CustomError::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overflow() -> Error {
+        error!(CustomError::MathOverflow)
+    }
+
+    #[test]
+    fn mul_div_rounds_as_named() {
+        assert_eq!(mul_div_floor(10, 3, 4).unwrap(), 7);
+        assert_eq!(mul_div_ceil(10, 3, 4).unwrap(), 8);
+        assert_eq!(mul_div_ceil(8, 3, 4).unwrap(), 6);
+    }
+
+    #[test]
+    fn mul_div_uses_wide_intermediates() {
+        assert_eq!(mul_div_floor(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+        assert_eq!(mul_div_floor(u64::MAX, 2, 1).unwrap_err(), overflow());
+        assert_eq!(mul_div_floor(1, 1, 0).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn fees_round_up() {
+        assert_eq!(fee_amount(1, 20).unwrap(), 1);
+        assert_eq!(fee_amount(10_000, 20).unwrap(), 20);
+        assert_eq!(fee_amount(0, 20).unwrap(), 0);
+    }
+
+    #[test]
+    fn utilization_is_capped_and_rounds_up() {
+        assert_eq!(utilization_bps(1, 3).unwrap(), 3_334);
+        assert_eq!(utilization_bps(5, 3).unwrap(), BPS_DENOMINATOR);
+        assert_eq!(utilization_bps(0, 0).unwrap(), BPS_DENOMINATOR);
+    }
+
+    #[test]
+    fn kinked_fee_follows_both_slopes() {
+        // 15 bps base, +5 bps to an 80% kink, +30 bps to 100%.
+        assert_eq!(kinked_fee_bps(15, 5, 30, 8_000, 0).unwrap(), 15);
+        assert_eq!(kinked_fee_bps(15, 5, 30, 8_000, 4_000).unwrap(), 18);
+        assert_eq!(kinked_fee_bps(15, 5, 30, 8_000, 8_000).unwrap(), 20);
+        assert_eq!(kinked_fee_bps(15, 5, 30, 8_000, 9_000).unwrap(), 35);
+        assert_eq!(kinked_fee_bps(15, 5, 30, 8_000, BPS_DENOMINATOR).unwrap(), 50);
+    }
+
+    #[test]
+    fn kinked_fee_is_continuous_and_monotonic() {
+        let mut previous = kinked_fee_bps(15, 50, 3_000, 8_000, 0).unwrap();
+        for utilization in 1..=BPS_DENOMINATOR {
+            let fee = kinked_fee_bps(15, 50, 3_000, 8_000, utilization).unwrap();
+            assert!(fee >= previous);
+            // The steepest segment moves 3,000 bps over 2,000 bps of utilization, plus rounding.
+            assert!(fee - previous <= 2);
+            previous = fee;
+        }
+    }
+
+    #[test]
+    fn kinked_fee_rejects_degenerate_kink() {
+        assert_eq!(kinked_fee_bps(15, 5, 30, 0, 0).unwrap_err(), error!(CustomError::InvalidFeeCurve));
+        assert_eq!(
+            kinked_fee_bps(15, 5, 30, BPS_DENOMINATOR, 0).unwrap_err(),
+            error!(CustomError::InvalidFeeCurve)
+        );
+    }
+
+    #[test]
+    fn late_penalty_rounds_up_and_reports_overflow() {
+        assert_eq!(late_penalty(1_000, 3, 50).unwrap(), 15);
+        assert_eq!(late_penalty(1, 1, 1).unwrap(), 1);
+        assert_eq!(late_penalty(u64::MAX, u64::MAX, u64::MAX).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn repayment_covers_penalty_pro_rata() {
+        // 1% per slot, 10 slots late: each unit of principal costs 1.1.
+        assert_eq!(split_repayment(1_000, 1_000, 0, 100).unwrap(), (1_000, 0));
+        assert_eq!(split_repayment(2_000, 1_000, 10, 100).unwrap(), (1_000, 100));
+        assert_eq!(split_repayment(550, 1_000, 10, 100).unwrap(), (500, 50));
+        assert_eq!(split_repayment(400, 1_000, 0, 100).unwrap(), (400, 0));
+        // Never takes more than was paid, even with the penalty rounded up.
+        for paid in 0..200 {
+            let (repaid, penalty) = split_repayment(paid, 1_000, 3, 7).unwrap();
+            assert!(repaid + penalty <= paid);
+            assert_eq!(penalty, late_penalty(repaid, 3, 7).unwrap());
+        }
+    }
+
+    #[test]
+    fn payouts_round_down() {
+        assert_eq!(liquidation_bonus(999, 50).unwrap(), 4);
+        assert_eq!(max_borrow(999, 5_000).unwrap(), 499);
+        assert_eq!(reward_share(3, 5_000).unwrap(), 1);
+    }
+
+    #[test]
+    fn seizure_is_capped_by_position_and_debt() {
+        assert_eq!(seize_position(10_000, 1_000, 50).unwrap(), (1_050, 50));
+        assert_eq!(seize_position(600, 1_000, 50).unwrap(), (600, 50));
+        assert_eq!(seize_position(30, 1_000, 50).unwrap(), (30, 30));
+        assert_eq!(seize_position(0, 1_000, 50).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn covered_liquidation_has_no_shortfall() {
+        assert_eq!(liquidation_shortfall(1_000, 1_000).unwrap(), 0);
+        assert_eq!(liquidation_shortfall(1_000, 1_200).unwrap(), 0);
+        assert_eq!(loss_waterfall(0, 500).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn insurance_absorbs_shortfall_it_can_cover() {
+        // 600 seized, 50 of it to the liquidator: 450 of the 1,000 principal is uncovered.
+        let (seized, bonus) = seize_position(600, 1_000, 50).unwrap();
+        let shortfall = liquidation_shortfall(1_000, seized - bonus).unwrap();
+        assert_eq!(shortfall, 450);
+        assert_eq!(loss_waterfall(shortfall, 1_000).unwrap(), (450, 0));
+        assert_eq!(loss_waterfall(shortfall, 450).unwrap(), (450, 0));
+    }
+
+    #[test]
+    fn escrow_liquidation_converts_debt_at_the_exchange_rate() {
+        // 3,000 assets backing 1,000 FLT: with the virtual offsets, 2 collateral per FLT.
+        // 1,000 principal + 50 penalty is 525 FLT, 25 of which pay the liquidator's 50 bonus.
+        assert_eq!(liquidate_escrow(1_000, 50, 10_000, 3_000, 1_000).unwrap(), (525, 50, 0));
+        // An escrow of 300 FLT recovers 275 * 2 = 550 after the bonus: 450 is uncovered.
+        assert_eq!(liquidate_escrow(1_000, 50, 300, 3_000, 1_000).unwrap(), (300, 50, 450));
+        // The bonus comes first, so a dust escrow recovers nothing.
+        assert_eq!(liquidate_escrow(1_000, 50, 10, 3_000, 1_000).unwrap(), (10, 20, 1_000));
+    }
+
+    #[test]
+    fn shortfall_beyond_insurance_is_socialized() {
+        let (seized, bonus) = seize_position(30, 1_000, 50).unwrap();
+        let shortfall = liquidation_shortfall(1_000, seized - bonus).unwrap();
+        assert_eq!(shortfall, 1_000);
+        assert_eq!(loss_waterfall(shortfall, 400).unwrap(), (400, 600));
+        assert_eq!(loss_waterfall(shortfall, 0).unwrap(), (0, 1_000));
+    }
+
+    #[test]
+    fn fee_shares_round_down() {
+        assert_eq!(insurance_share(999, 1_000).unwrap(), 99);
+        assert_eq!(insurance_share(999, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn early_adopters_get_boosted_weight() {
+        let threshold = early_adopter_threshold(6).unwrap();
+        assert_eq!(threshold, 10_000_000_000);
+        assert_eq!(boosted_weight(101, 0, threshold).unwrap(), 151);
+        assert_eq!(boosted_weight(101, threshold, threshold).unwrap(), 101);
+        assert_eq!(boosted_weight(101, threshold - 1, threshold).unwrap(), 151);
+        assert_eq!(early_adopter_threshold(30).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn compounding_does_not_overflow_u64_intermediates() {
+        // weight * rate * slots exceeds u64, the result does not.
+        let weight = 1_000_000_000_000;
+        assert_eq!(compound_rewards(weight, 1, 100, 1_000_000_000).unwrap(), 10_000_000_000_000_000_000);
+        assert_eq!(compound_rewards(weight, 1, 0, 1).unwrap_err(), overflow());
+        assert_eq!(compound_rewards(u64::MAX, u64::MAX, 1, 2).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn escrow_weight_leaves_nothing_behind() {
+        // A boosted 1.5x stake: half the escrow takes half the weight, the rest takes the remainder.
+        assert_eq!(escrow_weight(1_500, 500, 1_000).unwrap(), 750);
+        assert_eq!(escrow_weight(750, 500, 500).unwrap(), 750);
+        assert_eq!(escrow_weight(10, 1, 3).unwrap(), 4);
+        assert_eq!(escrow_weight(7, 0, 0).unwrap(), 7);
+    }
+
+    #[test]
+    fn shares_round_trip_never_gains() {
+        let (assets, supply) = (1_000_003, 999_999);
+        let shares = shares_for_deposit(12_345, assets, supply).unwrap();
+        assert!(assets_for_shares(shares, assets + 12_345, supply + shares).unwrap() <= 12_345);
+        assert_eq!(shares_for_deposit(7, 0, 0).unwrap(), 7);
+        assert_eq!(assets_for_shares(7, 0, 0).unwrap(), 7);
+    }
+
+    #[test]
+    fn donation_cannot_zero_out_later_deposits() {
+        // The attacker mints 1 FLT into an empty vault, then donates 1,000,000 collateral.
+        let supply = shares_for_deposit(1, 0, 0).unwrap();
+        assert_eq!(supply, 1);
+        let assets = 1 + 1_000_000;
+        // A victim depositing 10,000 still receives FLT...
+        let victim = shares_for_deposit(10_000, assets, supply).unwrap();
+        assert!(victim > 0);
+        // ...and the attacker recovers under 0.2% of the donation.
+        let attacker = assets_for_shares(supply, assets + 10_000, supply + victim).unwrap();
+        assert!(attacker < 2_000);
+    }
+
+    #[test]
+    fn vault_assets_reports_underflow() {
+        assert_eq!(vault_assets(10, 5, 3).unwrap(), 12);
+        assert_eq!(vault_assets(1, 0, 2).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn reward_accumulator_never_over_distributes() {
+        let increment = acc_fee_per_share_increment(10, 3).unwrap();
+        let per_staker = accumulated_rewards(1, increment).unwrap();
+        assert!(per_staker * 3 <= 10);
+        assert_eq!(acc_fee_per_share_increment(10, 0).unwrap_err(), overflow());
+    }
+}
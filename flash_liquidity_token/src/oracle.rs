@@ -0,0 +1,261 @@
+//
+// Oracle
+//
+// Turns Pyth prices into fixed-point values and derives the oracle fee adjustment.
+//
+
+use anchor_lang::prelude::*;
+use pyth_sdk_solana::{load_price_feed_from_account_info, Price};
+
+use crate::{math, CustomError};
+
+/// Decimals of a normalized price (1.0 == 1_000_000_000).
+pub const PRICE_DECIMALS: i32 = 9;
+
+/// Fixed-point scale of a normalized price.
+pub const PRICE_SCALE: u64 = 1_000_000_000;
+
+/// Widest accepted confidence interval, in basis points of the price.
+pub const MAX_CONF_BPS: u64 = 200;
+
+/// Lower bound of the oracle fee multiplier, in basis points (0.5x).
+pub const MIN_FEE_MULTIPLIER_BPS: u64 = 5_000;
+
+/// Upper bound of the oracle fee multiplier, in basis points (2x).
+pub const MAX_FEE_MULTIPLIER_BPS: u64 = 20_000;
+
+/// Convert a Pyth price to a `PRICE_DECIMALS` fixed-point value using its exponent.
+/// Rejects non-positive prices and prices whose confidence interval exceeds `MAX_CONF_BPS`.
+pub fn normalize_price(price: &Price) -> Result<u64> {
+    require!(price.price > 0, CustomError::OraclePriceUnavailable);
+    let raw = price.price as u128;
+    let scaled_conf = (price.conf as u128).checked_mul(10_000).ok_or(CustomError::MathOverflow)?;
+    let max_scaled_conf = raw.checked_mul(MAX_CONF_BPS as u128).ok_or(CustomError::MathOverflow)?;
+    require!(scaled_conf <= max_scaled_conf, CustomError::OracleConfidenceTooWide);
+
+    let shift = PRICE_DECIMALS.checked_add(price.expo).ok_or(CustomError::OraclePriceUnavailable)?;
+    let normalized = if shift >= 0 {
+        10u128
+            .checked_pow(shift as u32)
+            .and_then(|factor| raw.checked_mul(factor))
+            .ok_or(CustomError::OraclePriceUnavailable)?
+    } else {
+        match 10u128.checked_pow(shift.unsigned_abs()) {
+            Some(divisor) => raw / divisor,
+            None => 0,
+        }
+    };
+    require!(normalized > 0, CustomError::OraclePriceUnavailable);
+    u64::try_from(normalized).map_err(|_| error!(CustomError::OraclePriceUnavailable))
+}
+
+/// Load a Pyth price account and return its normalized price.
+/// The price must have been published within `max_age_secs` of `current_time`.
+pub fn load_normalized_price(price_account: &AccountInfo, max_age_secs: u64, current_time: u64) -> Result<u64> {
+    let price_feed = load_price_feed_from_account_info(price_account)
+        .map_err(|_| error!(CustomError::OraclePriceUnavailable))?;
+    let price = price_feed
+        .get_price_no_older_than(current_time as i64, max_age_secs)
+        .ok_or(CustomError::OraclePriceStale)?;
+    normalize_price(&price)
+}
+
+/// Pick the price to use from a primary and an optional secondary source.
+///
+/// The primary price is used when it loads. When the primary fails (for example because it
+/// is stale), the secondary price is used instead. When both load, they must be within
+/// `max_deviation_bps` of the primary, or pricing halts with `OracleDeviationTooHigh`.
+/// With no usable secondary, the primary's error is returned.
+pub fn resolve_price(primary: Result<u64>, secondary: Option<Result<u64>>, max_deviation_bps: u64) -> Result<u64> {
+    match (primary, secondary) {
+        (Ok(primary), Some(Ok(secondary))) => {
+            let scaled_deviation = (primary.abs_diff(secondary) as u128)
+                .checked_mul(10_000)
+                .ok_or(CustomError::MathOverflow)?;
+            let max_scaled_deviation = (primary as u128)
+                .checked_mul(max_deviation_bps as u128)
+                .ok_or(CustomError::MathOverflow)?;
+            require!(scaled_deviation <= max_scaled_deviation, CustomError::OracleDeviationTooHigh);
+            Ok(primary)
+        }
+        (Ok(primary), _) => Ok(primary),
+        (Err(_), Some(Ok(secondary))) => Ok(secondary),
+        (Err(err), _) => Err(err),
+    }
+}
+
+/// Scale a fee-curve fee by the oracle price.
+///
+/// The fee moves inversely with the collateral price around the collateral's normalized
+/// `reference_price` (`FeeCurve::reference_price`): `fee * reference_price / price`, with the
+/// multiplier clamped to [`MIN_FEE_MULTIPLIER_BPS`, `MAX_FEE_MULTIPLIER_BPS`]. The result is
+/// always between half and double `fee_bps`. Rounds up, in favour of the vault.
+/// A zero `reference_price` leaves the fee unchanged.
+pub fn adjust_fee_bps(fee_bps: u64, normalized_price: u64, reference_price: u64) -> Result<u64> {
+    if reference_price == 0 {
+        return Ok(fee_bps);
+    }
+    let multiplier_bps = ((reference_price as u128) * (math::BPS_DENOMINATOR as u128) / normalized_price.max(1) as u128)
+        .clamp(MIN_FEE_MULTIPLIER_BPS as u128, MAX_FEE_MULTIPLIER_BPS as u128) as u64;
+    math::mul_div_ceil(fee_bps, multiplier_bps, math::BPS_DENOMINATOR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyth_sdk_solana::state::{AccountType, PriceAccount, PriceInfo, PriceStatus, MAGIC, VERSION_2};
+
+    const NOW: i64 = 1_700_000_000;
+    const MAX_AGE: u64 = 60;
+    const REFERENCE_PRICE: u64 = 100 * PRICE_SCALE;
+
+    /// Serialize a trading Pyth price account with the given aggregate price.
+    fn mock_price_account(price: i64, conf: u64, expo: i32, publish_time: i64) -> Vec<u8> {
+        let account = PriceAccount {
+            magic: MAGIC,
+            ver: VERSION_2,
+            atype: AccountType::Price as u32,
+            expo,
+            timestamp: publish_time,
+            agg: PriceInfo { price, conf, status: PriceStatus::Trading, ..Default::default() },
+            ..Default::default()
+        };
+        // SAFETY: `PriceAccount` is a `repr(C)` plain-old-data type.
+        unsafe {
+            std::slice::from_raw_parts(
+                &account as *const PriceAccount as *const u8,
+                std::mem::size_of::<PriceAccount>(),
+            )
+        }
+        .to_vec()
+    }
+
+    fn load(data: &mut [u8]) -> Result<u64> {
+        let key = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut lamports = 0;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, data, &owner, false, 0);
+        load_normalized_price(&info, MAX_AGE, NOW as u64)
+    }
+
+    fn price(price: i64, conf: u64, expo: i32) -> Price {
+        Price { price, conf, expo, publish_time: NOW }
+    }
+
+    #[test]
+    fn normalizes_negative_exponent() {
+        // $150.12345678 at expo -8.
+        assert_eq!(normalize_price(&price(15_012_345_678, 0, -8)).unwrap(), 150_123_456_780);
+    }
+
+    #[test]
+    fn normalizes_exponent_below_scale() {
+        // $1.0000000001 at expo -10 truncates to 9 decimals.
+        assert_eq!(normalize_price(&price(10_000_000_001, 0, -10)).unwrap(), PRICE_SCALE);
+    }
+
+    #[test]
+    fn normalizes_non_negative_exponent() {
+        assert_eq!(normalize_price(&price(42, 0, 0)).unwrap(), 42 * PRICE_SCALE);
+        assert_eq!(normalize_price(&price(42, 0, 2)).unwrap(), 4_200 * PRICE_SCALE);
+    }
+
+    #[test]
+    fn rejects_non_positive_price() {
+        assert!(normalize_price(&price(0, 0, -8)).is_err());
+        assert!(normalize_price(&price(-5, 0, -8)).is_err());
+    }
+
+    #[test]
+    fn rejects_wide_confidence() {
+        // 2% confidence is accepted, anything wider is not.
+        assert!(normalize_price(&price(10_000, 200, -2)).is_ok());
+        assert_eq!(
+            normalize_price(&price(10_000, 201, -2)).unwrap_err(),
+            error!(CustomError::OracleConfidenceTooWide)
+        );
+    }
+
+    #[test]
+    fn loads_mock_price_account() {
+        let mut data = mock_price_account(15_012_345_678, 1_000_000, -8, NOW - 10);
+        assert_eq!(load(&mut data).unwrap(), 150_123_456_780);
+    }
+
+    #[test]
+    fn rejects_stale_price_account() {
+        let mut data = mock_price_account(15_012_345_678, 1_000_000, -8, NOW - MAX_AGE as i64 - 1);
+        assert_eq!(load(&mut data).unwrap_err(), error!(CustomError::OraclePriceStale));
+    }
+
+    #[test]
+    fn rejects_wide_confidence_price_account() {
+        let mut data = mock_price_account(10_000_000_000, 300_000_000, -8, NOW);
+        assert!(load(&mut data).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_account_data() {
+        let mut data = vec![0u8; std::mem::size_of::<PriceAccount>()];
+        assert!(load(&mut data).is_err());
+    }
+
+    #[test]
+    fn resolves_to_primary_within_deviation() {
+        // 1% apart, 2% allowed.
+        assert_eq!(resolve_price(Ok(100 * PRICE_SCALE), Some(Ok(99 * PRICE_SCALE)), 200).unwrap(), 100 * PRICE_SCALE);
+        assert_eq!(resolve_price(Ok(100 * PRICE_SCALE), None, 200).unwrap(), 100 * PRICE_SCALE);
+    }
+
+    #[test]
+    fn halts_when_sources_disagree() {
+        assert_eq!(
+            resolve_price(Ok(100 * PRICE_SCALE), Some(Ok(97 * PRICE_SCALE)), 200).unwrap_err(),
+            error!(CustomError::OracleDeviationTooHigh)
+        );
+    }
+
+    #[test]
+    fn falls_back_to_secondary_when_primary_stale() {
+        let stale = Err(error!(CustomError::OraclePriceStale));
+        assert_eq!(resolve_price(stale, Some(Ok(97 * PRICE_SCALE)), 200).unwrap(), 97 * PRICE_SCALE);
+    }
+
+    #[test]
+    fn keeps_primary_when_secondary_stale() {
+        let stale = Err(error!(CustomError::OraclePriceStale));
+        assert_eq!(resolve_price(Ok(100 * PRICE_SCALE), Some(stale), 200).unwrap(), 100 * PRICE_SCALE);
+    }
+
+    #[test]
+    fn fails_without_usable_source() {
+        let stale = || Err(error!(CustomError::OraclePriceStale));
+        assert_eq!(resolve_price(stale(), None, 200).unwrap_err(), error!(CustomError::OraclePriceStale));
+        assert_eq!(resolve_price(stale(), Some(stale()), 200).unwrap_err(), error!(CustomError::OraclePriceStale));
+    }
+
+    #[test]
+    fn fee_unchanged_at_reference_price() {
+        assert_eq!(adjust_fee_bps(20, REFERENCE_PRICE, REFERENCE_PRICE).unwrap(), 20);
+    }
+
+    #[test]
+    fn fee_scales_inversely_with_price() {
+        assert_eq!(adjust_fee_bps(20, REFERENCE_PRICE * 5 / 4, REFERENCE_PRICE).unwrap(), 16);
+        assert_eq!(adjust_fee_bps(20, REFERENCE_PRICE * 4 / 5, REFERENCE_PRICE).unwrap(), 25);
+        // The reference is per collateral: a $1 stablecoin at $1 pays the curve fee.
+        assert_eq!(adjust_fee_bps(20, PRICE_SCALE, PRICE_SCALE).unwrap(), 20);
+    }
+
+    #[test]
+    fn fee_multiplier_is_bounded() {
+        assert_eq!(adjust_fee_bps(20, 1, REFERENCE_PRICE).unwrap(), 40);
+        assert_eq!(adjust_fee_bps(20, u64::MAX, REFERENCE_PRICE).unwrap(), 10);
+    }
+
+    #[test]
+    fn zero_reference_price_disables_adjustment() {
+        assert_eq!(adjust_fee_bps(20, 1, 0).unwrap(), 20);
+        assert_eq!(adjust_fee_bps(20, u64::MAX, 0).unwrap(), 20);
+    }
+}
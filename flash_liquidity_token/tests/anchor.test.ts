@@ -1,81 +1,752 @@
-import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import {
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
 import BN from 'bn.js';
 
 describe("flash-liquidity-token", () => {
-  it("updates governance parameters", async () => {
-    // Generate keypair for the governance account.
-    const governanceKp = new web3.Keypair();
+  // Derive the governance PDA using seeds: ["governance"].
+  const [governancePda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("governance")],
+    pg.program.programId
+  );
+
+  // Derive the per-collateral reward pool PDA using seeds: ["reward_pool", collateral mint].
+  const rewardPoolFor = (collateralMint: web3.PublicKey) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_pool"), collateralMint.toBuffer()],
+      pg.program.programId
+    )[0];
+
+  // Derive the FLT mint PDA using seeds: ["flt_mint", collateral mint].
+  const fltMintFor = (collateralMint: web3.PublicKey) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("flt_mint"), collateralMint.toBuffer()],
+      pg.program.programId
+    )[0];
+
+  // Derive the insurance fund token account PDA using seeds: ["insurance", collateral mint].
+  const insuranceFor = (collateralMint: web3.PublicKey) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("insurance"), collateralMint.toBuffer()],
+      pg.program.programId
+    )[0];
+
+  // Derive the collateral config PDA using seeds: ["collateral_config", collateral mint].
+  const collateralConfigFor = (collateralMint: web3.PublicKey) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral_config"), collateralMint.toBuffer()],
+      pg.program.programId
+    )[0];
+
+  // Sample governance parameters. A zero timelock delay keeps direct updates available.
+  const sampleGovernanceParams = (
+    timelockDelaySlots: number,
+    voteCollateralMint: web3.PublicKey = web3.PublicKey.default
+  ) => ({
+    liquidationGraceSlots: new BN(10),    // in slots
+    compoundRateNumerator: new BN(1),
+    compoundRateDenominator: new BN(100),
+    rewardShareBps: new BN(5000),         // 50% of fees claimable by stakers
+    insuranceShareBps: new BN(1000),      // 10% of fees set aside for bad debt
+    timelockDelaySlots: new BN(timelockDelaySlots),
+    voteCollateralMint: voteCollateralMint, // collateral whose FLT votes
+    votingPeriodSlots: new BN(100),
+    quorumBps: new BN(1000),              // 10% of FLT supply must vote
+    approvalBps: new BN(5000),            // simple majority of votes cast
+  });
+
+  // Derive a pending parameter change PDA using seeds: ["pending_change", id].
+  const pendingChangeFor = (id: BN) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pending_change"), id.toArrayLike(Buffer, "le", 8)],
+      pg.program.programId
+    )[0];
+
+  // Sample risk parameters for a listed collateral.
+  const sampleCollateralParams = (pythFeed: web3.PublicKey) => ({
+    maxBorrowRatio: new BN(5000),         // 50.00% of collateral
+    liquidationPenaltyBps: new BN(50),    // 0.50% per overdue slot
+    borrowCap: new BN(1_000_000_000_000), // principal cap in base units
+    pythFeed: pythFeed,
+    maxPriceAgeSecs: new BN(60),          // reject Pyth prices older than a minute
+    secondaryOracle: { manual: {} },      // fall back to the governance-pushed price
+    maxDeviationBps: new BN(200),         // halt if the two sources differ by more than 2%
+    feeCurve: {
+      baseFeeBps: new BN(15),             // 0.15% at 0% utilization
+      slope1Bps: new BN(5),               // rising to 0.20% at the kink
+      slope2Bps: new BN(30),              // rising to 0.50% at 100% utilization
+      optimalUtilizationBps: new BN(8000), // kink at 80% utilization
+      referencePrice: new BN(100_000_000_000), // curve fee unchanged at 100 quote units (9 decimals)
+    },
+  });
+
+  // The collateral listed by the "lists a collateral" test.
+  let listedCollateralMint: web3.PublicKey;
+
+  // Derive the borrower state PDA (["borrower", borrower]) and the PDA of the borrower's
+  // next loan (["loan", borrower, collateral mint, nonce]).
+  const nextLoanFor = async (collateralMint: web3.PublicKey) => {
+    const [borrowerStatePda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("borrower"), pg.wallet.publicKey.toBuffer()],
+      pg.program.programId
+    );
+    const borrowerState = await pg.program.account.borrowerState.fetchNullable(borrowerStatePda);
+    const nonce: BN = borrowerState ? borrowerState.nextLoanNonce : new BN(0);
+    const [loanPda] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("loan"),
+        pg.wallet.publicKey.toBuffer(),
+        collateralMint.toBuffer(),
+        nonce.toArrayLike(Buffer, "le", 8),
+      ],
+      pg.program.programId
+    );
+    return { borrowerStatePda, loanPda };
+  };
+
+  it("initializes the protocol", async () => {
+    // Create the Governance PDA with sample parameters.
+    const txHash = await pg.program.methods
+      .initialize(sampleGovernanceParams(0))
+      .accounts({
+        admin: pg.wallet.publicKey,
+        governance: governancePda,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log("Initialize txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+
+    const governance = await pg.program.account.governance.fetch(governancePda);
+    assert(governance.admin.equals(pg.wallet.publicKey));
+  });
+
+  it("lists a collateral", async () => {
+    // Create a fresh collateral mint owned by the wallet.
+    const collateralMint = await createMint(
+      pg.connection,
+      pg.wallet.keypair,
+      pg.wallet.publicKey,
+      null,
+      6
+    );
+
+    // Derive the per-collateral PDAs.
+    const [vaultPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), collateralMint.toBuffer()],
+      pg.program.programId
+    );
+    const [vaultTokenPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_token"), collateralMint.toBuffer()],
+      pg.program.programId
+    );
+    const [fltMintPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("flt_mint"), collateralMint.toBuffer()],
+      pg.program.programId
+    );
+    const [fltMintWrapperPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("flt_mint_wrapper"), collateralMint.toBuffer()],
+      pg.program.programId
+    );
+
+    const pythPriceKp = new web3.Keypair();
+    const txHash = await pg.program.methods
+      .listCollateral(sampleCollateralParams(pythPriceKp.publicKey))
+      .accounts({
+        listingManager: pg.wallet.publicKey,
+        governance: governancePda,
+        collateralMint: collateralMint,
+        collateralConfig: collateralConfigFor(collateralMint),
+        vaultAccount: vaultPda,
+        vaultTokenAccount: vaultTokenPda,
+        insuranceTokenAccount: insuranceFor(collateralMint),
+        fltMint: fltMintPda,
+        fltMintWrapper: fltMintWrapperPda,
+        rewardPool: rewardPoolFor(collateralMint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+        rent: web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    console.log("List collateral txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+
+    const collateralConfig = await pg.program.account.collateralConfig.fetch(
+      collateralConfigFor(collateralMint)
+    );
+    assert(collateralConfig.collateralMint.equals(collateralMint));
+    assert.deepEqual(collateralConfig.status, { active: {} });
+    listedCollateralMint = collateralMint;
+  });
+
+  it("sets a collateral to reduce-only", async () => {
+    let event = null;
+    const listener = pg.program.addEventListener("CollateralUpdated", (e) => {
+      event = e;
+    });
+
+    // The listing manager (the admin by default) changes the listing status.
+    const txHash = await pg.program.methods
+      .updateCollateral({ reduceOnly: {} })
+      .accounts({
+        listingManager: pg.wallet.publicKey,
+        governance: governancePda,
+        collateralConfig: collateralConfigFor(listedCollateralMint),
+      })
+      .rpc();
+    console.log("Update collateral txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+    await pg.program.removeEventListener(listener);
+
+    const updated = await pg.program.account.collateralConfig.fetch(
+      collateralConfigFor(listedCollateralMint)
+    );
+    assert.deepEqual(updated.status, { reduceOnly: {} });
+
+    // The event carries the resulting settings.
+    assert(event !== null);
+    assert(event.authority.equals(pg.wallet.publicKey));
+    assert(event.collateralMint.equals(listedCollateralMint));
+    assert.deepEqual(event.status, { reduceOnly: {} });
+    assert(event.params.borrowCap.eq(updated.borrowCap));
+  });
+
+  it("tightens a collateral's risk parameters", async () => {
+    // The risk manager (the admin by default) lowers the borrow cap.
+    const config = await pg.program.account.collateralConfig.fetch(
+      collateralConfigFor(listedCollateralMint)
+    );
+    const txHash = await pg.program.methods
+      .setCollateralRisk({
+        maxBorrowRatio: config.maxBorrowRatio,
+        liquidationPenaltyBps: config.liquidationPenaltyBps,
+        borrowCap: new BN(500_000_000_000),
+        pythFeed: config.pythFeed,
+        maxPriceAgeSecs: config.maxPriceAgeSecs,
+        secondaryOracle: config.secondaryOracle,
+        maxDeviationBps: config.maxDeviationBps,
+      })
+      .accounts({
+        riskManager: pg.wallet.publicKey,
+        governance: governancePda,
+        collateralConfig: collateralConfigFor(listedCollateralMint),
+      })
+      .rpc();
+    console.log("Set collateral risk txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+
+    const updated = await pg.program.account.collateralConfig.fetch(
+      collateralConfigFor(listedCollateralMint)
+    );
+    assert(updated.borrowCap.eq(new BN(500_000_000_000)));
+  });
+
+  it("pushes a manual fallback price", async () => {
+    // The risk manager (the admin by default) pushes $1.00, normalized to 9 decimals.
+    const [manualPricePda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("manual_price"), listedCollateralMint.toBuffer()],
+      pg.program.programId
+    );
+    const txHash = await pg.program.methods
+      .setManualPrice(new BN(1_000_000_000))
+      .accounts({
+        riskManager: pg.wallet.publicKey,
+        governance: governancePda,
+        collateralConfig: collateralConfigFor(listedCollateralMint),
+        manualPrice: manualPricePda,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log("Set manual price txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+
+    const manualPrice = await pg.program.account.manualPrice.fetch(manualPricePda);
+    assert(manualPrice.price.eq(new BN(1_000_000_000)));
+  });
+
+  it("reads the FLT exchange rate", async () => {
+    // Use the collateral listed above.
+    const collateralMint = listedCollateralMint;
 
+    const [vaultTokenPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_token"), collateralMint.toBuffer()],
+      pg.program.programId
+    );
+    const [fltMintPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("flt_mint"), collateralMint.toBuffer()],
+      pg.program.programId
+    );
+
+    // A freshly listed vault has no FLT supply, so the rate is exactly 1.0.
+    const rate = await pg.program.methods
+      .exchangeRate()
+      .accounts({
+        collateralMint: collateralMint,
+        fltMint: fltMintPda,
+        vaultTokenAccount: vaultTokenPda,
+        rewardPool: rewardPoolFor(collateralMint),
+      })
+      .view();
+    console.log("Exchange rate:", rate.toString());
+    assert(rate.eq(new BN(1_000_000_000)));
+  });
+
+  it("updates governance parameters", async () => {
     // Call updateGovernanceParameters with sample values.
     const txHash = await pg.program.methods
-      .updateGovernanceParameters(
-        new BN(20),   // flash_loan_fee_bps (default fee, unused in dynamic mode)
-        new BN(50),   // liquidation_penalty_bps (e.g., 0.50%)
-        new BN(10),   // liquidation_grace_slots (in slots)
-        new BN(1),    // compound_rate_numerator
-        new BN(100),  // compound_rate_denominator
-        new BN(5000)  // max_borrow_ratio (50.00%)
-      )
+      .updateGovernanceParameters(sampleGovernanceParams(0, listedCollateralMint))
       .accounts({
         admin: pg.wallet.publicKey,
-        governance: governanceKp.publicKey,
+        governance: governancePda,
       })
-      .signers([governanceKp])
       .rpc();
     console.log("Governance updated, txHash:", txHash);
     await pg.connection.confirmTransaction(txHash);
   });
 
-  it("stakes collateral", async () => {
-    // Generate keypairs for collateral mint and FLT mint.
-    const collateralMintKp = new web3.Keypair();
-    const fltMintKp = new web3.Keypair();
-    const fltMintWrapper = { bump: 255 }; // Dummy bump value for the FLT mint PDA.
-
-    // Generate token accounts for the user and the vault.
-    const userTokenAccountKp = new web3.Keypair();
-    const vaultTokenAccountKp = new web3.Keypair();
-    const userFlTokenAccountKp = new web3.Keypair();
-
-    // Derive the staker PDA using seeds: ["staker", user public key, collateral mint]
-    const [stakerPda, _stakerBump] = await web3.PublicKey.findProgramAddress(
-      [
-        Buffer.from("staker"),
-        pg.wallet.publicKey.toBuffer(),
-        collateralMintKp.publicKey.toBuffer(),
-      ],
+  it("transfers admin in two steps", async () => {
+    // Nominate the current admin as its own successor, then accept.
+    const proposeTx = await pg.program.methods
+      .proposeAdmin(pg.wallet.publicKey)
+      .accounts({
+        admin: pg.wallet.publicKey,
+        governance: governancePda,
+      })
+      .rpc();
+    await pg.connection.confirmTransaction(proposeTx);
+
+    const acceptTx = await pg.program.methods
+      .acceptAdmin()
+      .accounts({
+        pendingAdmin: pg.wallet.publicKey,
+        governance: governancePda,
+      })
+      .rpc();
+    console.log("Accept admin txHash:", acceptTx);
+    await pg.connection.confirmTransaction(acceptTx);
+
+    const governance = await pg.program.account.governance.fetch(governancePda);
+    assert(governance.admin.equals(pg.wallet.publicKey));
+    assert(governance.pendingAdmin.equals(web3.PublicKey.default));
+  });
+
+  it("assigns a separate guardian", async () => {
+    const guardianKp = new web3.Keypair();
+    const txHash = await pg.program.methods
+      .setRoles({
+        feeManager: pg.wallet.publicKey,
+        riskManager: pg.wallet.publicKey,
+        listingManager: pg.wallet.publicKey,
+        guardian: guardianKp.publicKey,
+      })
+      .accounts({
+        admin: pg.wallet.publicKey,
+        governance: governancePda,
+      })
+      .rpc();
+    console.log("Set roles txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+
+    const governance = await pg.program.account.governance.fetch(governancePda);
+    assert(governance.roles.guardian.equals(guardianKp.publicKey));
+  });
+
+  it("pauses and resumes borrowing", async () => {
+    // Flag values mirror the PAUSE_* constants in the program.
+    const PAUSE_BORROW = 1 << 2;
+
+    const pauseTx = await pg.program.methods
+      .pause(PAUSE_BORROW)
+      .accounts({
+        authority: pg.wallet.publicKey,
+        governance: governancePda,
+      })
+      .rpc();
+    console.log("Pause txHash:", pauseTx);
+    await pg.connection.confirmTransaction(pauseTx);
+
+    let governance = await pg.program.account.governance.fetch(governancePda);
+    assert(governance.paused === PAUSE_BORROW);
+
+    const unpauseTx = await pg.program.methods
+      .unpause(PAUSE_BORROW)
+      .accounts({
+        authority: pg.wallet.publicKey,
+        governance: governancePda,
+      })
+      .rpc();
+    console.log("Unpause txHash:", unpauseTx);
+    await pg.connection.confirmTransaction(unpauseTx);
+
+    governance = await pg.program.account.governance.fetch(governancePda);
+    assert(governance.paused === 0);
+  });
+
+  it("emits GovernanceUpdated on pause", async () => {
+    const PAUSE_COMPOUND = 1 << 5;
+
+    let event = null;
+    const listener = pg.program.addEventListener("GovernanceUpdated", (e) => {
+      event = e;
+    });
+
+    const pauseTx = await pg.program.methods
+      .pause(PAUSE_COMPOUND)
+      .accounts({
+        authority: pg.wallet.publicKey,
+        governance: governancePda,
+      })
+      .rpc();
+    await pg.connection.confirmTransaction(pauseTx);
+
+    const unpauseTx = await pg.program.methods
+      .unpause(PAUSE_COMPOUND)
+      .accounts({
+        authority: pg.wallet.publicKey,
+        governance: governancePda,
+      })
+      .rpc();
+    await pg.connection.confirmTransaction(unpauseTx);
+    await pg.program.removeEventListener(listener);
+
+    // The listener sees both events; the last one reflects the unpause.
+    assert(event !== null);
+    assert(event.authority.equals(pg.wallet.publicKey));
+    assert(event.paused === 0);
+  });
+
+  it("queues and executes a timelocked parameter change", async () => {
+    // With a zero delay the queued change is executable in the same slot.
+    const governance = await pg.program.account.governance.fetch(governancePda);
+    const pendingChangePda = pendingChangeFor(governance.nextChangeId);
+
+    const queueTx = await pg.program.methods
+      .queueParameterChange({ governance: { 0: sampleGovernanceParams(0, listedCollateralMint) } })
+      .accounts({
+        authority: pg.wallet.publicKey,
+        governance: governancePda,
+        pendingChange: pendingChangePda,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log("Queue change txHash:", queueTx);
+    await pg.connection.confirmTransaction(queueTx);
+
+    const executeTx = await pg.program.methods
+      .executeParameterChange()
+      .accounts({
+        authority: pg.wallet.publicKey,
+        governance: governancePda,
+        pendingChange: pendingChangePda,
+        collateralConfig: null,
+      })
+      .rpc();
+    console.log("Execute change txHash:", executeTx);
+    await pg.connection.confirmTransaction(executeTx);
+
+    // The pending record is closed once executed.
+    const closed = await pg.program.account.pendingChange.fetchNullable(pendingChangePda);
+    assert(closed === null);
+  });
+
+  it("cancels a queued parameter change", async () => {
+    const governance = await pg.program.account.governance.fetch(governancePda);
+    const pendingChangePda = pendingChangeFor(governance.nextChangeId);
+
+    const queueTx = await pg.program.methods
+      .queueParameterChange({ governance: { 0: sampleGovernanceParams(0, listedCollateralMint) } })
+      .accounts({
+        authority: pg.wallet.publicKey,
+        governance: governancePda,
+        pendingChange: pendingChangePda,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    await pg.connection.confirmTransaction(queueTx);
+
+    const cancelTx = await pg.program.methods
+      .cancelParameterChange()
+      .accounts({
+        authority: pg.wallet.publicKey,
+        governance: governancePda,
+        pendingChange: pendingChangePda,
+      })
+      .rpc();
+    console.log("Cancel change txHash:", cancelTx);
+    await pg.connection.confirmTransaction(cancelTx);
+
+    const closed = await pg.program.account.pendingChange.fetchNullable(pendingChangePda);
+    assert(closed === null);
+  });
+
+  it("lets each role queue only the changes it owns", async () => {
+    const feeManagerKp = new web3.Keypair();
+    const airdropTx = await pg.connection.requestAirdrop(feeManagerKp.publicKey, web3.LAMPORTS_PER_SOL);
+    await pg.connection.confirmTransaction(airdropTx);
+
+    const before = await pg.program.account.governance.fetch(governancePda);
+    const setRoles = (feeManager: web3.PublicKey) =>
+      pg.program.methods
+        .setRoles({ ...before.roles, feeManager })
+        .accounts({ admin: pg.wallet.publicKey, governance: governancePda })
+        .rpc();
+    await pg.connection.confirmTransaction(await setRoles(feeManagerKp.publicKey));
+
+    // The fee manager queues and executes a fee change.
+    const feeChangePda = pendingChangeFor(before.nextChangeId);
+    await pg.program.methods
+      .queueParameterChange({ feeParameters: { rewardShareBps: new BN(2000), insuranceShareBps: new BN(500) } })
+      .accounts({
+        authority: feeManagerKp.publicKey,
+        governance: governancePda,
+        pendingChange: feeChangePda,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([feeManagerKp])
+      .rpc();
+    await pg.program.methods
+      .executeParameterChange()
+      .accounts({
+        authority: feeManagerKp.publicKey,
+        governance: governancePda,
+        pendingChange: feeChangePda,
+        collateralConfig: null,
+      })
+      .signers([feeManagerKp])
+      .rpc();
+    const updated = await pg.program.account.governance.fetch(governancePda);
+    assert(updated.rewardShareBps.eq(new BN(2000)));
+    assert(updated.insuranceShareBps.eq(new BN(500)));
+
+    // The grace period belongs to the risk manager.
+    let error = null;
+    try {
+      await pg.program.methods
+        .queueParameterChange({ liquidationGraceSlots: { 0: new BN(1) } })
+        .accounts({
+          authority: feeManagerKp.publicKey,
+          governance: governancePda,
+          pendingChange: pendingChangeFor(updated.nextChangeId),
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([feeManagerKp])
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    assert(error !== null && error.error.errorCode.code === "Unauthorized");
+
+    await pg.connection.confirmTransaction(await setRoles(before.roles.feeManager));
+  });
+
+  it("creates an FLT-weighted proposal", async () => {
+    // Proposals snapshot the FLT supply of the governance voting collateral.
+    const [fltMintPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("flt_mint"), listedCollateralMint.toBuffer()],
       pg.program.programId
     );
-
-    // Derive the vault PDA using seeds: ["vault", collateral mint]
-    const [vaultPda, _vaultBump] = await web3.PublicKey.findProgramAddress(
-      [Buffer.from("vault"), collateralMintKp.publicKey.toBuffer()],
+    const [voterRecordPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("voter"), pg.wallet.publicKey.toBuffer()],
       pg.program.programId
     );
+    const governance = await pg.program.account.governance.fetch(governancePda);
+    const [proposalPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), governance.nextProposalId.toArrayLike(Buffer, "le", 8)],
+      pg.program.programId
+    );
+
+    // The proposer needs escrowed FLT (deposited with depositVotes) to create a proposal.
+    const txHash = await pg.program.methods
+      .createProposal({ governance: { 0: sampleGovernanceParams(0, listedCollateralMint) } })
+      .accounts({
+        proposer: pg.wallet.publicKey,
+        governance: governancePda,
+        fltMint: fltMintPda,
+        voterRecord: voterRecordPda,
+        proposal: proposalPda,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log("Create proposal txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+
+    const proposal = await pg.program.account.proposal.fetch(proposalPda);
+    assert(proposal.yesVotes.eq(new BN(0)));
+    assert(!proposal.executed);
+  });
+
+  // Collateral used by the lending tests below: listed with an empty account as its Pyth feed,
+  // so pricing falls back to the manual price pushed for it.
+  let lending: ReturnType<typeof lendingAccountsFor>;
+
+  // Virtual FLT and collateral units on each side of the exchange rate (see `math`).
+  const VIRTUAL = new BN(1000);
+
+  // Derive every account the lending instructions need for `mint` and the wallet.
+  const lendingAccountsFor = (mint: web3.PublicKey, userTokenAccount: web3.PublicKey) => {
+    const pda = (...seeds: Buffer[]) =>
+      web3.PublicKey.findProgramAddressSync(seeds, pg.program.programId)[0];
+    return {
+      mint,
+      userTokenAccount,
+      pythFeed: new web3.Keypair().publicKey,
+      vault: pda(Buffer.from("vault"), mint.toBuffer()),
+      vaultToken: pda(Buffer.from("vault_token"), mint.toBuffer()),
+      insurance: insuranceFor(mint),
+      fltMint: fltMintFor(mint),
+      fltMintWrapper: pda(Buffer.from("flt_mint_wrapper"), mint.toBuffer()),
+      manualPrice: pda(Buffer.from("manual_price"), mint.toBuffer()),
+      staker: pda(Buffer.from("staker"), pg.wallet.publicKey.toBuffer(), mint.toBuffer()),
+      stakeEscrow: pda(Buffer.from("stake_escrow"), pg.wallet.publicKey.toBuffer(), mint.toBuffer()),
+      collateralConfig: collateralConfigFor(mint),
+      rewardPool: rewardPoolFor(mint),
+    };
+  };
 
-    // Generate new keypairs for governance and reward pool accounts.
-    const governanceKp = new web3.Keypair();
-    const rewardPoolKp = new web3.Keypair();
+  const tokenBalance = async (address: web3.PublicKey) =>
+    new BN((await getAccount(pg.connection, address)).amount.toString());
 
-    // Define the collateral amount and lock duration.
-    const stakeAmount = new BN(1000000);
-    const lockDuration = new BN(100);
+  // Collateral backing FLT: vault balance plus principal lent out, less reserved rewards.
+  const vaultAssets = async () => {
+    const pool = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    return (await tokenBalance(lending.vaultToken)).add(pool.activeLoanTotal).sub(pool.reservedRewards);
+  };
 
-    // Call the stake instruction.
+  // Run `request` and check that it fails with the program error `code`.
+  const expectError = async (request: Promise<unknown>, code: string) => {
+    let error = null;
+    try {
+      await request;
+    } catch (err) {
+      error = err;
+    }
+    assert(error !== null, `expected ${code}`);
+    assert.equal(error.error.errorCode.code, code);
+  };
+
+  const borrowAccounts = (loan: web3.PublicKey, borrowerState: web3.PublicKey) => ({
+    borrower: pg.wallet.publicKey,
+    borrowerTokenAccount: lending.userTokenAccount,
+    vaultAccount: lending.vault,
+    vaultTokenAccount: lending.vaultToken,
+    staker: lending.staker,
+    borrowerState: borrowerState,
+    loan: loan,
+    governance: governancePda,
+    collateralConfig: lending.collateralConfig,
+    rewardPool: lending.rewardPool,
+    fltMint: lending.fltMint,
+    pythPrice: lending.pythFeed,
+    secondaryPrice: lending.manualPrice,
+    instructionsSysvar: web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: web3.SystemProgram.programId,
+    rent: web3.SYSVAR_RENT_PUBKEY,
+    insuranceTokenAccount: lending.insurance,
+  });
+
+  const repayAccounts = (loan: web3.PublicKey, borrowerState: web3.PublicKey) => ({
+    borrower: pg.wallet.publicKey,
+    borrowerTokenAccount: lending.userTokenAccount,
+    vaultAccount: lending.vault,
+    vaultTokenAccount: lending.vaultToken,
+    loan: loan,
+    staker: lending.staker,
+    governance: governancePda,
+    collateralConfig: lending.collateralConfig,
+    rewardPool: lending.rewardPool,
+    borrowerState: borrowerState,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    insuranceTokenAccount: lending.insurance,
+  });
+
+  // Open a term loan of `amount` due `duration` slots from now and return its PDAs.
+  const openTermLoan = async (amount: BN, duration: BN) => {
+    const { borrowerStatePda, loanPda } = await nextLoanFor(lending.mint);
     const txHash = await pg.program.methods
-      .stake(stakeAmount, lockDuration)
+      .borrow(amount, duration, { term: {} })
+      .accounts(borrowAccounts(loanPda, borrowerStatePda))
+      .rpc();
+    await pg.connection.confirmTransaction(txHash);
+    return { borrowerStatePda, loanPda };
+  };
+
+  // The term loan opened by "borrows liquidity" and closed by "repays loan".
+  let termLoan: { borrowerStatePda: web3.PublicKey; loanPda: web3.PublicKey };
+
+  it("lists a lending collateral with a manual price", async () => {
+    const mint = await createMint(pg.connection, pg.wallet.keypair, pg.wallet.publicKey, null, 6);
+    const userTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(pg.connection, pg.wallet.keypair, mint, pg.wallet.publicKey)
+    ).address;
+    await mintTo(pg.connection, pg.wallet.keypair, mint, userTokenAccount, pg.wallet.keypair, 1_000_000_000);
+    lending = lendingAccountsFor(mint, userTokenAccount);
+
+    const listTx = await pg.program.methods
+      .listCollateral(sampleCollateralParams(lending.pythFeed))
+      .accounts({
+        listingManager: pg.wallet.publicKey,
+        governance: governancePda,
+        collateralMint: mint,
+        collateralConfig: lending.collateralConfig,
+        vaultAccount: lending.vault,
+        vaultTokenAccount: lending.vaultToken,
+        insuranceTokenAccount: lending.insurance,
+        fltMint: lending.fltMint,
+        fltMintWrapper: lending.fltMintWrapper,
+        rewardPool: lending.rewardPool,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+        rent: web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    await pg.connection.confirmTransaction(listTx);
+
+    // The reference price, so the curve fee is not adjusted.
+    const priceTx = await pg.program.methods
+      .setManualPrice(new BN(100_000_000_000))
+      .accounts({
+        riskManager: pg.wallet.publicKey,
+        governance: governancePda,
+        collateralConfig: lending.collateralConfig,
+        manualPrice: lending.manualPrice,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    await pg.connection.confirmTransaction(priceTx);
+
+    // The insurance fund is its own, empty token account owned by the vault PDA.
+    const insurance = await getAccount(pg.connection, lending.insurance);
+    assert(insurance.owner.equals(lending.vault));
+    assert(insurance.mint.equals(mint));
+    assert.equal(insurance.amount.toString(), "0");
+  });
+
+  it("stakes collateral", async () => {
+    const stakeAmount = new BN(100_000_000);
+    const userBefore = await tokenBalance(lending.userTokenAccount);
+
+    // No lock, so the stake can be unstaked once its loans are repaid.
+    const txHash = await pg.program.methods
+      .stake(stakeAmount, new BN(0))
       .accounts({
         user: pg.wallet.publicKey,
-        userTokenAccount: userTokenAccountKp.publicKey,
-        vaultTokenAccount: vaultTokenAccountKp.publicKey,
-        collateralMint: collateralMintKp.publicKey,
-        fltMint: fltMintKp.publicKey,
-        fltMintWrapper: fltMintWrapper,
-        userFltTokenAccount: userFlTokenAccountKp.publicKey,
-        governance: governanceKp.publicKey,
-        rewardPool: rewardPoolKp.publicKey,
-        staker: stakerPda,
-        vaultAccount: vaultPda,
+        userTokenAccount: lending.userTokenAccount,
+        vaultTokenAccount: lending.vaultToken,
+        collateralMint: lending.mint,
+        fltMint: lending.fltMint,
+        fltMintWrapper: lending.fltMintWrapper,
+        stakeEscrow: lending.stakeEscrow,
+        governance: governancePda,
+        collateralConfig: lending.collateralConfig,
+        rewardPool: lending.rewardPool,
+        staker: lending.staker,
+        vaultAccount: lending.vault,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: web3.SystemProgram.programId,
         rent: web3.SYSVAR_RENT_PUBKEY,
@@ -83,90 +754,404 @@ describe("flash-liquidity-token", () => {
       .rpc();
     console.log("Stake txHash:", txHash);
     await pg.connection.confirmTransaction(txHash);
+
+    // An empty vault mints FLT 1:1 into the escrow; the early-adopter boost raises the weight.
+    assert(userBefore.sub(await tokenBalance(lending.userTokenAccount)).eq(stakeAmount));
+    assert((await tokenBalance(lending.vaultToken)).eq(stakeAmount));
+    assert((await tokenBalance(lending.stakeEscrow)).eq(stakeAmount));
+    const staker = await pg.program.account.staker.fetch(lending.staker);
+    assert(staker.escrowedFlt.eq(stakeAmount));
+    assert(staker.stakedAmount.eq(stakeAmount.muln(3).divn(2)));
+    const pool = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    assert(pool.totalStaked.eq(staker.stakedAmount));
   });
 
   it("borrows liquidity", async () => {
-    // Generate keypairs for collateral mint and FLT mint.
-    const collateralMintKp = new web3.Keypair();
-    const fltMintKp = new web3.Keypair();
-    const fltMintWrapper = { bump: 255 };
-
-    // Generate token accounts.
-    const userTokenAccountKp = new web3.Keypair();
-    const vaultTokenAccountKp = new web3.Keypair();
-    const userFlTokenAccountKp = new web3.Keypair();
-
-    // Derive PDAs for staker and vault.
-    const [stakerPda, _stakerBump] = await web3.PublicKey.findProgramAddress(
-      [
-        Buffer.from("staker"),
-        pg.wallet.publicKey.toBuffer(),
-        collateralMintKp.publicKey.toBuffer(),
-      ],
-      pg.program.programId
-    );
-    const [vaultPda, _vaultBump] = await web3.PublicKey.findProgramAddress(
-      [Buffer.from("vault"), collateralMintKp.publicKey.toBuffer()],
-      pg.program.programId
+    const governance = await pg.program.account.governance.fetch(governancePda);
+    const poolBefore = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    const userBefore = await tokenBalance(lending.userTokenAccount);
+
+    // Term loans are capped at 50% of the escrowed FLT's collateral value.
+    const { borrowerStatePda, loanPda } = await nextLoanFor(lending.mint);
+    await expectError(
+      pg.program.methods
+        .borrow(new BN(60_000_000), new BN(50), { term: {} })
+        .accounts(borrowAccounts(loanPda, borrowerStatePda))
+        .rpc(),
+      "BorrowAmountExceedsCollateral"
     );
 
-    // Generate new keypairs for governance and reward pool accounts.
-    const governanceKp = new web3.Keypair();
-    const rewardPoolKp = new web3.Keypair();
+    const borrowAmount = new BN(10_000_000);
+    termLoan = await openTermLoan(borrowAmount, new BN(50));
 
-    // Create dummy accounts for the callback program and Pyth price.
-    const callbackProgramKp = new web3.Keypair();
-    const pythPriceKp = new web3.Keypair();
+    const loan = await pg.program.account.loan.fetch(termLoan.loanPda);
+    assert(loan.active);
+    assert(loan.amount.eq(borrowAmount));
+    assert(loan.dueSlot.sub(loan.startSlot).eq(new BN(50)));
+    const staker = await pg.program.account.staker.fetch(lending.staker);
+    assert(staker.borrowed.eq(borrowAmount));
 
-    // Generate a new loan account.
-    const loanKp = new web3.Keypair();
+    // The fee is withheld from the payout; its insurance share moves to the insurance account.
+    const pool = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    const fee = pool.accruedFees.sub(poolBefore.accruedFees);
+    assert(fee.gtn(0));
+    assert((await tokenBalance(lending.userTokenAccount)).sub(userBefore).eq(borrowAmount.sub(fee)));
+    assert(pool.activeLoanTotal.eq(borrowAmount));
+    const insuranceShare = fee.mul(governance.insuranceShareBps).divn(10_000);
+    assert(pool.insuranceFund.eq(insuranceShare));
+    assert((await tokenBalance(lending.insurance)).eq(insuranceShare));
+  });
 
-    // Define borrow amount and loan duration.
-    const borrowAmount = new BN(500000);
-    const loanDuration = new BN(50);
+  it("extends a term loan", async () => {
+    const before = await pg.program.account.loan.fetch(termLoan.loanPda);
+    const poolBefore = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    const userBefore = await tokenBalance(lending.userTokenAccount);
 
-    // Call the borrow instruction.
+    // Push the due slot 50 slots further out, paying the current curve fee.
     const txHash = await pg.program.methods
-      .borrow(borrowAmount, loanDuration)
+      .extendLoan(new BN(50))
       .accounts({
         borrower: pg.wallet.publicKey,
-        borrowerTokenAccount: userTokenAccountKp.publicKey,
-        vaultAccount: vaultPda,
-        vaultTokenAccount: vaultTokenAccountKp.publicKey,
-        staker: stakerPda,
-        loan: loanKp.publicKey,
-        governance: governanceKp.publicKey,
-        rewardPool: rewardPoolKp.publicKey,
-        callbackProgram: callbackProgramKp.publicKey,
-        pythPrice: pythPriceKp.publicKey,
+        borrowerTokenAccount: lending.userTokenAccount,
+        vaultTokenAccount: lending.vaultToken,
+        loan: termLoan.loanPda,
+        governance: governancePda,
+        collateralConfig: lending.collateralConfig,
+        rewardPool: lending.rewardPool,
+        pythPrice: lending.pythFeed,
+        secondaryPrice: lending.manualPrice,
         tokenProgram: TOKEN_PROGRAM_ID,
-        systemProgram: web3.SystemProgram.programId,
-        rent: web3.SYSVAR_RENT_PUBKEY,
+        vaultAccount: lending.vault,
+        insuranceTokenAccount: lending.insurance,
       })
-      .signers([loanKp])
       .rpc();
-    console.log("Borrow txHash:", txHash);
+    console.log("Extend loan txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+
+    const loan = await pg.program.account.loan.fetch(termLoan.loanPda);
+    assert(loan.dueSlot.eq(before.dueSlot.addn(50)));
+    assert(loan.amount.eq(before.amount));
+    const pool = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    const fee = pool.accruedFees.sub(poolBefore.accruedFees);
+    assert(fee.gtn(0));
+    assert(userBefore.sub(await tokenBalance(lending.userTokenAccount)).eq(fee));
+  });
+
+  it("flash borrows with a same-transaction repay", async () => {
+    const borrowAmount = new BN(5_000_000);
+    const { borrowerStatePda, loanPda } = await nextLoanFor(lending.mint);
+    const borrowerStateBefore = await pg.program.account.borrowerState.fetch(borrowerStatePda);
+
+    // Without a matching repay later in the transaction the flash borrow is rejected.
+    await expectError(
+      pg.program.methods
+        .borrow(borrowAmount, new BN(0), { flash: {} })
+        .accounts(borrowAccounts(loanPda, borrowerStatePda))
+        .rpc(),
+      "FlashRepayMissing"
+    );
+
+    // The repay must appear later in the same transaction for the same loan and vault.
+    const repayIx = await pg.program.methods
+      .repay(borrowAmount)
+      .accounts(repayAccounts(loanPda, borrowerStatePda))
+      .instruction();
+    const txHash = await pg.program.methods
+      .borrow(borrowAmount, new BN(0), { flash: {} })
+      .accounts(borrowAccounts(loanPda, borrowerStatePda))
+      .postInstructions([repayIx])
+      .rpc();
+    console.log("Flash borrow txHash:", txHash);
     await pg.connection.confirmTransaction(txHash);
+
+    // The loan is closed by the repay and only the term loan stays open.
+    assert((await pg.program.account.loan.fetchNullable(loanPda)) === null);
+    const borrowerState = await pg.program.account.borrowerState.fetch(borrowerStatePda);
+    assert.equal(borrowerState.openLoans.toString(), borrowerStateBefore.openLoans.toString());
+    assert(borrowerState.nextLoanNonce.eq(borrowerStateBefore.nextLoanNonce.addn(1)));
+  });
+
+  it("executes a single-instruction flash loan", async () => {
+    const amount = new BN(5_000_000);
+    // The token program is the callback: the forwarded data is a Transfer (instruction 3)
+    // of `repaid` from the borrower back to the vault, signed by the wallet.
+    const flashLoan = (repaid: BN) =>
+      pg.program.methods
+        .flashLoan(amount, Buffer.concat([Buffer.from([3]), repaid.toArrayLike(Buffer, "le", 8)]))
+        .accounts({
+          borrower: pg.wallet.publicKey,
+          borrowerTokenAccount: lending.userTokenAccount,
+          collateralMint: lending.mint,
+          vaultAccount: lending.vault,
+          vaultTokenAccount: lending.vaultToken,
+          governance: governancePda,
+          collateralConfig: lending.collateralConfig,
+          rewardPool: lending.rewardPool,
+          callbackProgram: TOKEN_PROGRAM_ID,
+          pythPrice: lending.pythFeed,
+          secondaryPrice: lending.manualPrice,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          insuranceTokenAccount: lending.insurance,
+        })
+        .remainingAccounts([
+          { pubkey: lending.userTokenAccount, isSigner: false, isWritable: true },
+          { pubkey: lending.vaultToken, isSigner: false, isWritable: true },
+          { pubkey: pg.wallet.publicKey, isSigner: true, isWritable: false },
+        ])
+        .rpc();
+
+    // Returning only the principal leaves the fee unpaid.
+    await expectError(flashLoan(amount), "RepaymentFeeMissing");
+
+    // Returning principal plus 1% covers the fee; the excess stays in the vault.
+    const poolBefore = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    const heldBefore = (await tokenBalance(lending.vaultToken)).add(await tokenBalance(lending.insurance));
+    const userBefore = await tokenBalance(lending.userTokenAccount);
+    const txHash = await flashLoan(amount.add(amount.divn(100)));
+    console.log("Flash loan txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+
+    const pool = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    assert(pool.accruedFees.gt(poolBefore.accruedFees));
+    assert(!pool.flashLoanActive);
+    const held = (await tokenBalance(lending.vaultToken)).add(await tokenBalance(lending.insurance));
+    assert(held.sub(heldBefore).eq(amount.divn(100)));
+    assert(userBefore.sub(await tokenBalance(lending.userTokenAccount)).eq(amount.divn(100)));
   });
 
-  // Additional tests (such as for repay, compoundRewards, unstake, liquidate) can be added below.
   it("repays loan", async () => {
-    // Insert test logic for repay here.
-    console.log("Repay test not implemented yet.");
+    const before = await pg.program.account.loan.fetch(termLoan.loanPda);
+    const borrowerStateBefore = await pg.program.account.borrowerState.fetch(termLoan.borrowerStatePda);
+    const userBefore = await tokenBalance(lending.userTokenAccount);
+    const repay = (amount: BN) =>
+      pg.program.methods
+        .repay(amount)
+        .accounts(repayAccounts(termLoan.loanPda, termLoan.borrowerStatePda))
+        .rpc();
+
+    // A partial repayment before the due slot pays no penalty and keeps the loan open.
+    const partial = before.amount.divn(4);
+    await pg.connection.confirmTransaction(await repay(partial));
+    const loan = await pg.program.account.loan.fetch(termLoan.loanPda);
+    assert(loan.amount.eq(before.amount.sub(partial)));
+    let staker = await pg.program.account.staker.fetch(lending.staker);
+    assert(staker.borrowed.eq(loan.amount));
+    assert(userBefore.sub(await tokenBalance(lending.userTokenAccount)).eq(partial));
+
+    // Repaying the rest closes the loan and releases the stake.
+    const txHash = await repay(loan.amount);
+    console.log("Repay txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+    assert((await pg.program.account.loan.fetchNullable(termLoan.loanPda)) === null);
+    assert(userBefore.sub(await tokenBalance(lending.userTokenAccount)).eq(before.amount));
+    staker = await pg.program.account.staker.fetch(lending.staker);
+    assert(staker.borrowed.eqn(0));
+    const borrowerState = await pg.program.account.borrowerState.fetch(termLoan.borrowerStatePda);
+    assert(borrowerState.openLoans.eq(borrowerStateBefore.openLoans.subn(1)));
+    const pool = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    assert(pool.activeLoanTotal.eqn(0));
   });
 
   it("compounds rewards", async () => {
-    // Insert test logic for compoundRewards here.
-    console.log("Compound rewards test not implemented yet.");
+    const before = await pg.program.account.staker.fetch(lending.staker);
+    const poolBefore = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+
+    const txHash = await pg.program.methods
+      .compoundRewards()
+      .accounts({
+        staker: lending.staker,
+        stakerOwner: pg.wallet.publicKey,
+        governance: governancePda,
+        rewardPool: lending.rewardPool,
+      })
+      .rpc();
+    console.log("Compound rewards txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+
+    // Slots have passed since staking, so the weight grows; the escrowed FLT does not.
+    const staker = await pg.program.account.staker.fetch(lending.staker);
+    const added = staker.stakedAmount.sub(before.stakedAmount);
+    assert(added.gtn(0));
+    assert(staker.lastCompoundSlot.gt(before.lastCompoundSlot));
+    assert(staker.escrowedFlt.eq(before.escrowedFlt));
+    const pool = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    assert(pool.totalStaked.eq(poolBefore.totalStaked.add(added)));
+  });
+
+  it("claims rewards", async () => {
+    const poolBefore = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    const userBefore = await tokenBalance(lending.userTokenAccount);
+    assert(poolBefore.reservedRewards.gtn(0));
+
+    let event = null;
+    const listener = pg.program.addEventListener("RewardsClaimed", (e) => {
+      event = e;
+    });
+
+    // Pays out the staker's share of fees credited since their last settlement.
+    const claim = () =>
+      pg.program.methods
+        .claimRewards()
+        .accounts({
+          user: pg.wallet.publicKey,
+          userTokenAccount: lending.userTokenAccount,
+          vaultAccount: lending.vault,
+          vaultTokenAccount: lending.vaultToken,
+          staker: lending.staker,
+          governance: governancePda,
+          rewardPool: lending.rewardPool,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    const txHash = await claim();
+    console.log("Claim rewards txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+    await pg.program.removeEventListener(listener);
+
+    // The sole staker receives the reserved fees, less accumulator rounding.
+    const claimed = (await tokenBalance(lending.userTokenAccount)).sub(userBefore);
+    assert(claimed.gtn(0));
+    const pool = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    assert(pool.reservedRewards.eq(poolBefore.reservedRewards.sub(claimed)));
+    const staker = await pg.program.account.staker.fetch(lending.staker);
+    assert(staker.pendingRewards.eqn(0));
+    assert(event !== null && event.amount.eq(claimed));
+    assert(event.owner.equals(pg.wallet.publicKey) && event.collateralMint.equals(lending.mint));
+
+    // Nothing is left to claim right away.
+    await expectError(claim(), "NoRewardsToClaim");
   });
 
-  it("unstakes collateral", async () => {
-    // Insert test logic for unstake here.
-    console.log("Unstake test not implemented yet.");
+  it("unstakes collateral by burning escrowed FLT", async () => {
+    const before = await pg.program.account.staker.fetch(lending.staker);
+    const userBefore = await tokenBalance(lending.userTokenAccount);
+    const supply = new BN((await getMint(pg.connection, lending.fltMint)).supply.toString());
+    const assets = await vaultAssets();
+
+    // Unstaking burns FLT from the caller's escrow and redeems the matching collateral.
+    const amount = new BN(10_000_000);
+    const txHash = await pg.program.methods
+      .unstake(amount)
+      .accounts({
+        user: pg.wallet.publicKey,
+        userTokenAccount: lending.userTokenAccount,
+        collateralMint: lending.mint,
+        fltMint: lending.fltMint,
+        fltMintWrapper: lending.fltMintWrapper,
+        stakeEscrow: lending.stakeEscrow,
+        vaultAccount: lending.vault,
+        vaultTokenAccount: lending.vaultToken,
+        governance: governancePda,
+        collateralConfig: lending.collateralConfig,
+        staker: lending.staker,
+        rewardPool: lending.rewardPool,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    console.log("Unstake txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+
+    // Fees raised the exchange rate, so each FLT redeems more than one collateral unit.
+    const redeemed = amount.mul(assets.add(VIRTUAL)).div(supply.add(VIRTUAL));
+    assert(redeemed.gt(amount));
+    assert((await tokenBalance(lending.userTokenAccount)).sub(userBefore).eq(redeemed));
+    const staker = await pg.program.account.staker.fetch(lending.staker);
+    assert(staker.escrowedFlt.eq(before.escrowedFlt.sub(amount)));
+    assert(staker.stakedAmount.lt(before.stakedAmount));
+    assert((await tokenBalance(lending.stakeEscrow)).eq(staker.escrowedFlt));
   });
 
   it("liquidates overdue loan", async () => {
-    // Insert test logic for liquidate here.
-    console.log("Liquidation test not implemented yet.");
+    const setGraceSlots = async (slots: BN) =>
+      pg.connection.confirmTransaction(
+        await pg.program.methods
+          .setLiquidationGraceSlots(slots)
+          .accounts({ riskManager: pg.wallet.publicKey, governance: governancePda })
+          .rpc()
+      );
+    const { liquidationGraceSlots } = await pg.program.account.governance.fetch(governancePda);
+
+    // A liquidator with its own collateral account for the bonus.
+    const liquidatorKp = new web3.Keypair();
+    const airdropTx = await pg.connection.requestAirdrop(liquidatorKp.publicKey, web3.LAMPORTS_PER_SOL);
+    await pg.connection.confirmTransaction(airdropTx);
+    const liquidatorTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(pg.connection, pg.wallet.keypair, lending.mint, liquidatorKp.publicKey)
+    ).address;
+
+    // A term loan due right away.
+    const borrowAmount = new BN(10_000_000);
+    const { borrowerStatePda, loanPda } = await openTermLoan(borrowAmount, new BN(0));
+    const liquidate = () =>
+      pg.program.methods
+        .liquidate()
+        .accounts({
+          liquidator: liquidatorKp.publicKey,
+          liquidatorTokenAccount: liquidatorTokenAccount,
+          vaultAccount: lending.vault,
+          vaultTokenAccount: lending.vaultToken,
+          loan: loanPda,
+          staker: lending.staker,
+          governance: governancePda,
+          collateralConfig: lending.collateralConfig,
+          rewardPool: lending.rewardPool,
+          borrowerState: borrowerStatePda,
+          pythPrice: lending.pythFeed,
+          secondaryPrice: lending.manualPrice,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          fltMint: lending.fltMint,
+          stakeEscrow: lending.stakeEscrow,
+          insuranceTokenAccount: lending.insurance,
+          borrower: pg.wallet.publicKey,
+        })
+        .signers([liquidatorKp])
+        .rpc();
+
+    // Still inside the grace period.
+    if (liquidationGraceSlots.gtn(0)) {
+      await expectError(liquidate(), "LoanNotOverdue");
+    }
+
+    // Without a grace period the loan is overdue from the next slot.
+    await setGraceSlots(new BN(0));
+    const loan = await pg.program.account.loan.fetch(loanPda);
+    while ((await pg.connection.getSlot()) <= loan.dueSlot.toNumber()) {
+      await new Promise((resolve) => setTimeout(resolve, 200));
+    }
+
+    // Expected seizure: FLT worth principal plus penalty, rounded up; the penalty's worth of it,
+    // rounded down, pays the bonus. The escrow covers all of it, so nothing reaches the
+    // insurance fund or bad debt.
+    const config = await pg.program.account.collateralConfig.fetch(lending.collateralConfig);
+    const penalty = borrowAmount.mul(config.liquidationPenaltyBps).divn(10_000);
+    const supply = new BN((await getMint(pg.connection, lending.fltMint)).supply.toString());
+    const assets = (await vaultAssets()).add(VIRTUAL);
+    const shares = supply.add(VIRTUAL);
+    const seizedExpected = borrowAmount.add(penalty).mul(shares).add(assets).subn(1).div(assets);
+    const bonus = penalty.mul(shares).div(assets).mul(assets).div(shares);
+    const stakerBefore = await pg.program.account.staker.fetch(lending.staker);
+    const poolBefore = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    const insuranceBefore = await tokenBalance(lending.insurance);
+
+    const txHash = await liquidate();
+    console.log("Liquidate txHash:", txHash);
+    await pg.connection.confirmTransaction(txHash);
+    await setGraceSlots(liquidationGraceSlots);
+
+    // The loan is closed and the bonus paid out of the borrower's seized escrow.
+    assert((await pg.program.account.loan.fetchNullable(loanPda)) === null);
+    assert(bonus.gtn(0));
+    assert((await tokenBalance(liquidatorTokenAccount)).eq(bonus));
+    const staker = await pg.program.account.staker.fetch(lending.staker);
+    const seized = stakerBefore.escrowedFlt.sub(staker.escrowedFlt);
+    assert(seized.eq(seizedExpected) && seized.lt(stakerBefore.escrowedFlt));
+    assert(staker.borrowed.eqn(0));
+    assert(supply.sub(new BN((await getMint(pg.connection, lending.fltMint)).supply.toString())).eq(seized));
+    const pool = await pg.program.account.rewardPool.fetch(lending.rewardPool);
+    assert(pool.activeLoanTotal.eqn(0));
+    assert(pool.badDebt.eq(poolBefore.badDebt));
+    assert(pool.insuranceFund.eq(poolBefore.insuranceFund));
+    assert(pool.accruedFees.eq(poolBefore.accruedFees));
+    assert((await tokenBalance(lending.insurance)).eq(insuranceBefore));
   });
 });